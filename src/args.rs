@@ -11,6 +11,22 @@ use clap::{
 )]
 pub struct RudderArgs{
 
+    /// Output format. `json` prints one JSON event per line and a final report, for editors and scripts.
+    #[clap(long, global = true, default_value = "human", value_parser = ["human", "json"])]
+    pub output: String,
+
+    /// Only print errors and warnings.
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Also print the folders created, the files updated and the commands run.
+    #[clap(short, long, global = true)]
+    pub verbose: bool,
+
+    /// What to do with an existing file rudder would overwrite, when it was edited or not generated by rudder. Defaults to fail, or to skip for `create`.
+    #[clap(long, global = true, value_parser = ["skip", "overwrite", "backup", "fail"])]
+    pub on_conflict: Option<String>,
+
     /// Subcommand to execute.
     #[clap(subcommand)]
     pub command: RudderCommand,
@@ -19,25 +35,348 @@ pub struct RudderArgs{
 #[derive(Subcommand, Debug)]
 pub enum RudderCommand {
     /// Create a Flutter project folder structure.
-    Create,
+    Create(CreateCommand),
 
     /// Add a feature/sub feature to the Flutter project.
     Add(AddCommand),
+
+    /// Import an API description into the Flutter project.
+    #[clap(subcommand)]
+    Import(ImportCommand),
+
+    /// Manage the keys of the .env file and its typed Env class.
+    #[clap(subcommand)]
+    Env(EnvCommand),
+
+    /// Manage the build flavors of the Flutter project.
+    #[clap(subcommand)]
+    Flavors(FlavorsCommand),
+
+    /// Manage the localizations of the Flutter project.
+    #[clap(subcommand, name = "l10n")]
+    L10n(L10nCommand),
+
+    /// Generate the light and dark themes and the AppColors palette from a seed color.
+    Theme(ThemeCommand),
+
+    /// Manage the design tokens of the Flutter project.
+    #[clap(subcommand)]
+    Tokens(TokensCommand),
+
+    /// List the presets of rudder create.
+    #[clap(subcommand)]
+    Presets(PresetsCommand),
+
+    /// Re-render the generated files with the current templates, merging in the edits made since.
+    Upgrade,
+
+    /// Show how the generated files differ from what the current templates produce.
+    Diff(DiffCommand),
+
+    /// Print a completion script for a shell. >> rudder completions bash > /etc/bash_completion.d/rudder
+    Completions(CompletionsCommand),
+
+    /// List the values a completion script offers, e.g. the features of the project.
+    #[command(hide = true)]
+    Complete(CompleteCommand),
 }
 
+#[derive(Args, Debug)]
+pub struct CreateCommand {
+    /// Ask for the answers below in a guided wizard. Falls back to the flags when not run in a terminal.
+    #[clap(short, long)]
+    pub interactive: bool,
+
+    /// What the project structure includes: minimal, standard, full or a preset of .rudder/presets. Defaults to standard.
+    #[clap(long)]
+    pub preset: Option<String>,
+
+    /// Title of the app. Defaults to the package name.
+    #[clap(long)]
+    pub app_title: Option<String>,
+
+    /// Name of the Dart package. Defaults to the name in pubspec.yaml.
+    #[clap(long)]
+    pub package_name: Option<String>,
+
+    /// How features expose their state.
+    #[clap(long, value_parser = ["riverpod", "riverpod_freezed"])]
+    pub state_management: Option<String>,
+
+    /// Router the screens are registered with.
+    #[clap(long, value_parser = ["auto_route", "go_router"])]
+    pub router: Option<String>,
+
+    /// Services to add. >> --services http_client,secure_storage
+    #[clap(long)]
+    pub services: Option<String>,
+
+    /// Features to start with. >> --features home,settings
+    #[clap(long, default_value = "home")]
+    pub features: String,
+
+    /// Don't install the dependencies.
+    #[clap(long)]
+    pub no_install: bool,
+}
 
 #[derive(Args, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct AddCommand {
+    /// Add something other than a feature to the Flutter project.
+    #[clap(subcommand)]
+    pub command: Option<AddSubcommand>,
+
     // /// Name of the feature to add. >> rudder add [feature_name].
     // #[clap(short, long)]
     // pub feature: Option<String>,
 
     /// Name of the features to add. >> rudder add [feature_name] [feature_name] ... 
-    #[clap(short, long)]
-    pub features: String,
+    #[clap(short, long, required = true)]
+    pub features: Option<String>,
 
     /// Name of the sub feature to add. >> rudder add [feature_name] [sub_feature_name]
     #[clap(short, long)]
     pub sub_feature: Option<String>,
+
+    /// UI states of the feature, generated as a freezed union. >> --states loading,empty,loaded:List<Item>,error:Object
+    #[clap(long)]
+    pub states: Option<String>,
+
+    /// Don't generate tests for the feature under test/.
+    #[clap(long)]
+    pub no_tests: bool,
+
+    /// Shape of the feature: basic, list-detail, form, readonly, settings or a blueprint of .rudder/blueprints. Defaults to basic.
+    #[clap(long)]
+    pub blueprint: Option<String>,
+}
+
+
+#[derive(Subcommand, Debug)]
+pub enum AddSubcommand {
+    /// Add a freezed model to a feature's models file.
+    Model(AddModelCommand),
+
+    /// Add a case to the AppException union and group it under a feature.
+    Exception(AddExceptionCommand),
+
+    /// Add a method to a feature's repository, fake, service and controller.
+    Method(AddMethodCommand),
+
+    /// Add a service to the services folder and register it in main.dart.
+    Service(AddServiceCommand),
+
+    /// Add a shared widget, or a widget local to a feature, with its test.
+    Widget(AddWidgetCommand),
 }
 
+#[derive(Args, Debug)]
+pub struct AddModelCommand {
+    /// Name of the feature that owns the model. >> rudder add model [feature_name] [ModelName]
+    pub feature: String,
+
+    /// Name of the model class. >> rudder add model [feature_name] [ModelName]
+    /// Defaults to the feature name when generating from JSON.
+    #[clap(required_unless_present = "from_json")]
+    pub name: Option<String>,
+
+    /// Fields of the model. >> --fields id:int,name:String,email:String?,tags:List<String>
+    #[clap(long, required_unless_present = "from_json", conflicts_with = "from_json")]
+    pub fields: Option<String>,
+
+    /// Sample JSON payload to infer the model (and any nested models) from. >> --from-json response.json
+    #[clap(long)]
+    pub from_json: Option<String>,
+
+    /// Name of the sub feature that owns the model, if any.
+    #[clap(short, long)]
+    pub sub_feature: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ImportCommand {
+    /// Generate models and a remote repository from an OpenAPI spec. >> rudder import openapi spec.yaml --feature orders
+    Openapi(ImportOpenapiCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct ImportOpenapiCommand {
+    /// Path to the OpenAPI spec (YAML or JSON).
+    pub spec: String,
+
+    /// Name of the feature to generate the repository into. Created if it doesn't exist.
+    #[clap(short, long)]
+    pub feature: String,
+
+    /// Only import the operations with this tag.
+    #[clap(short, long)]
+    pub tag: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct AddExceptionCommand {
+    /// Name of the feature raising the exception. >> rudder add exception [feature_name] [exceptionName] "[message]"
+    pub feature: String,
+
+    /// Name of the exception case. >> rudder add exception [feature_name] [exceptionName] "[message]"
+    pub name: String,
+
+    /// User facing message of the exception. >> rudder add exception [feature_name] [exceptionName] "[message]"
+    pub message: String,
+
+    /// Name of the sub feature raising the exception, if any.
+    #[clap(short, long)]
+    pub sub_feature: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct AddMethodCommand {
+    /// Name of the feature to add the method to. >> rudder add method [feature_name] [methodName]
+    pub feature: String,
+
+    /// Name of the method. >> rudder add method [feature_name] [methodName]
+    pub name: String,
+
+    /// Type returned by the method. >> --returns List<Order>
+    #[clap(long, default_value = "void")]
+    pub returns: String,
+
+    /// Parameters of the method. Nullable parameters become named. >> --params status:String,page:int?
+    #[clap(long)]
+    pub params: Option<String>,
+
+    /// Repository the service delegates to.
+    #[clap(long, default_value = "remote", value_parser = ["remote", "local"])]
+    pub repository: String,
+
+    /// Name of the sub feature to add the method to, if any.
+    #[clap(short, long)]
+    pub sub_feature: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct AddServiceCommand {
+    /// Kind of service: http_client, secure_storage, shared_preferences, connectivity, biometrics or analytics.
+    /// Any other name adds a blank service. >> rudder add service [kind|name]
+    pub name: String,
+}
+
+#[derive(Args, Debug)]
+pub struct AddWidgetCommand {
+    /// Name of the widget. >> rudder add widget [WidgetName]
+    pub name: String,
+
+    /// Feature owning the widget. Without it, the widget is added to shared_widgets.
+    #[clap(short, long)]
+    pub feature: Option<String>,
+
+    /// Sub feature owning the widget, if any.
+    #[clap(short, long, requires = "feature")]
+    pub sub_feature: Option<String>,
+
+    /// Kind of widget.
+    #[clap(short, long, default_value = "stateless", value_parser = ["stateless", "stateful", "consumer"])]
+    pub kind: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EnvCommand {
+    /// Add a key to .env and regenerate the Env class. >> rudder env add API_KEY --type string
+    Add(EnvAddCommand),
+
+    /// Regenerate the Env class from the keys of .env.
+    Sync,
+}
+
+#[derive(Args, Debug)]
+pub struct EnvAddCommand {
+    /// Name of the key. >> rudder env add [KEY]
+    pub key: String,
+
+    /// Type of the value. Inferred from the default when omitted.
+    #[clap(short, long, value_parser = ["string", "int", "bool", "url"])]
+    pub r#type: Option<String>,
+
+    /// Value used when the key is missing or empty in .env.
+    #[clap(short, long)]
+    pub default: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FlavorsCommand {
+    /// Add flavors with their own entry point and .env file. >> rudder flavors add dev,staging,prod
+    Add(FlavorsAddCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct FlavorsAddCommand {
+    /// Names of the flavors to add. >> rudder flavors add [flavor],[flavor],...
+    pub flavors: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum L10nCommand {
+    /// Create the ARB files and wire the localizations into app.dart. >> rudder l10n init --locales en,es
+    Init(L10nInitCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct L10nInitCommand {
+    /// Locales of the app. The first one is the template the others are translated from.
+    #[clap(short, long, default_value = "en")]
+    pub locales: String,
+}
+
+#[derive(Args, Debug)]
+pub struct ThemeCommand {
+    /// Seed color of the color schemes. Defaults to the theme section of .rudder/config.json. >> --seed "#6750A4"
+    #[clap(long)]
+    pub seed: Option<String>,
+
+    /// Theme mode the app starts with.
+    #[clap(long, value_parser = ["system", "light", "dark"])]
+    pub mode: Option<String>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PresetsCommand {
+    /// List the built-in presets and the ones of .rudder/presets. >> rudder presets list
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TokensCommand {
+    /// Regenerate app_colors, app_sizes and app_text_styles from a design tokens file. >> rudder tokens import tokens.json
+    Import(TokensImportCommand),
+}
+
+#[derive(Args, Debug)]
+pub struct TokensImportCommand {
+    /// Path to the design tokens JSON exported from Figma.
+    pub file: String,
+}
+
+#[derive(Args, Debug)]
+pub struct DiffCommand {
+    /// A generated file, a folder or a feature name. Defaults to every generated file.
+    pub target: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct CompletionsCommand {
+    /// Shell to generate the completion script for.
+    #[clap(value_parser = ["bash", "zsh", "fish", "powershell"])]
+    pub shell: String,
+}
+
+#[derive(Args, Debug)]
+pub struct CompleteCommand {
+    /// Kind of values to list.
+    #[clap(value_parser = ["features", "sub-features"])]
+    pub kind: String,
+
+    /// Command line being completed, used to find the feature owning the sub features.
+    #[clap(long)]
+    pub line: Option<String>,
+}
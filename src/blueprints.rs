@@ -0,0 +1,309 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::presets;
+
+/// Folder of the project's own blueprints, one folder per blueprint holding a blueprint.json and its templates.
+pub const BLUEPRINTS_PATH: &str = ".rudder\\blueprints";
+
+pub const DEFAULT_BLUEPRINT: &str = "basic";
+
+/// The files, routes and dependencies of a feature added with `rudder add --blueprint`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Blueprint {
+    #[serde(skip)]
+    pub name: String,
+    pub description: String,
+    /// Built-in blueprint a custom blueprint starts from.
+    pub extends: Option<String>,
+    /// Files of the feature, relative to its folder.
+    pub files: Vec<BlueprintFile>,
+    /// Files of the feature's tests, relative to its folder under test/.
+    pub tests: Vec<BlueprintFile>,
+    /// Screens registered with go_router.
+    pub routes: Vec<BlueprintRoute>,
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+    /// Folder of a custom blueprint, `None` for the built-in ones.
+    #[serde(skip)]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlueprintFile {
+    /// `{feature}` is replaced by the name of the feature, e.g. `presentation/{feature}_screen.dart`.
+    pub path: String,
+    /// A file of the blueprint's folder, or else the id of a built-in template.
+    pub template: String,
+    /// Values the template is rendered with, on top of the ones of the feature.
+    #[serde(default)]
+    pub variables: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BlueprintRoute {
+    /// Name of the screen, e.g. `{feature}_detail` for presentation/{feature}_detail_screen.dart.
+    pub screen: String,
+    /// Path under the route of the feature, e.g. `:id`. Its parameters are given to the screen.
+    #[serde(default)]
+    pub path: String,
+}
+
+#[derive(Clone, Copy)]
+struct BuiltinFile {
+    path: &'static str,
+    template: &'static str,
+    /// Shape of the service and repositories, see `FEATURE_SHAPES` in dart.rs.
+    shape: Option<&'static str>,
+}
+
+struct BuiltinBlueprint {
+    name: &'static str,
+    description: &'static str,
+    files: &'static [BuiltinFile],
+    tests: &'static [BuiltinFile],
+    /// Screen and path of each route.
+    routes: &'static [(&'static str, &'static str)],
+    dependencies: &'static [&'static str],
+}
+
+const fn file(path: &'static str, template: &'static str) -> BuiltinFile {
+    BuiltinFile { path, template, shape: None }
+}
+
+const fn shaped(path: &'static str, template: &'static str, shape: &'static str) -> BuiltinFile {
+    BuiltinFile { path, template, shape: Some(shape) }
+}
+
+const DOMAIN_FILES: [BuiltinFile; 3] = [
+    file("domain/{feature}_models.dart", "empty"),
+    file("domain/{feature}_exceptions.dart", "feature_exceptions"),
+    file("domain/{feature}_unions.dart", "union_states"),
+];
+
+const BUILTIN_BLUEPRINTS: [BuiltinBlueprint; 5] = [
+    BuiltinBlueprint {
+        name: "basic",
+        description: "Service, local and remote repositories with their fakes, a screen and its controller",
+        files: &[
+            file("application/{feature}_service.dart", "application_service"),
+            file("data/{feature}_local_repository.dart", "local_repository"),
+            file("data/{feature}_remote_repository.dart", "remote_repository"),
+            file("data/fake_{feature}_local_repository.dart", "fake_local_repository"),
+            file("data/fake_{feature}_remote_repository.dart", "fake_remote_repository"),
+            DOMAIN_FILES[0],
+            DOMAIN_FILES[1],
+            DOMAIN_FILES[2],
+            file("presentation/{feature}_screen.dart", "screen"),
+            file("presentation/controllers/{feature}_controller.dart", "controller"),
+        ],
+        tests: &[
+            file("test_overrides.dart", "test_overrides"),
+            file("application/{feature}_service_test.dart", "service_test"),
+            file("presentation/controllers/{feature}_controller_test.dart", "controller_test"),
+            file("presentation/{feature}_screen_test.dart", "screen_test"),
+        ],
+        routes: &[("{feature}", "")],
+        dependencies: &[],
+    },
+    BuiltinBlueprint {
+        name: "list-detail",
+        description: "A list of items loaded from the remote repository, opening a detail screen",
+        files: &[
+            shaped("application/{feature}_service.dart", "shape_service", "list"),
+            shaped("data/{feature}_remote_repository.dart", "shape_repository", "list"),
+            shaped("data/fake_{feature}_remote_repository.dart", "shape_fake_repository", "list"),
+            DOMAIN_FILES[0],
+            DOMAIN_FILES[1],
+            DOMAIN_FILES[2],
+            file("presentation/{feature}_screen.dart", "list_screen"),
+            file("presentation/{feature}_detail_screen.dart", "detail_screen"),
+            file("presentation/controllers/{feature}_controller.dart", "list_controller"),
+        ],
+        tests: &[
+            file("test_overrides.dart", "test_overrides"),
+            file("application/{feature}_service_test.dart", "service_test"),
+            file("presentation/{feature}_screen_test.dart", "screen_test"),
+        ],
+        routes: &[("{feature}", ""), ("{feature}_detail", ":id")],
+        dependencies: &[],
+    },
+    BuiltinBlueprint {
+        name: "form",
+        description: "A validated form submitted to the remote repository",
+        files: &[
+            shaped("application/{feature}_service.dart", "shape_service", "form"),
+            shaped("data/{feature}_remote_repository.dart", "shape_repository", "form"),
+            shaped("data/fake_{feature}_remote_repository.dart", "shape_fake_repository", "form"),
+            DOMAIN_FILES[0],
+            DOMAIN_FILES[1],
+            DOMAIN_FILES[2],
+            file("presentation/{feature}_screen.dart", "form_screen"),
+            file("presentation/controllers/{feature}_controller.dart", "form_controller"),
+        ],
+        tests: &[
+            file("test_overrides.dart", "test_overrides"),
+            file("application/{feature}_service_test.dart", "service_test"),
+            file("presentation/{feature}_screen_test.dart", "screen_test"),
+        ],
+        routes: &[("{feature}", "")],
+        dependencies: &[],
+    },
+    BuiltinBlueprint {
+        name: "readonly",
+        description: "Data fetched from the remote repository and shown with a refresh action, without saving",
+        files: &[
+            shaped("application/{feature}_service.dart", "shape_service", "readonly"),
+            shaped("data/{feature}_remote_repository.dart", "shape_repository", "readonly"),
+            shaped("data/fake_{feature}_remote_repository.dart", "shape_fake_repository", "readonly"),
+            DOMAIN_FILES[0],
+            DOMAIN_FILES[1],
+            DOMAIN_FILES[2],
+            file("presentation/{feature}_screen.dart", "readonly_screen"),
+            file("presentation/controllers/{feature}_controller.dart", "readonly_controller"),
+        ],
+        tests: &[
+            file("test_overrides.dart", "test_overrides"),
+            file("application/{feature}_service_test.dart", "service_test"),
+            file("presentation/{feature}_screen_test.dart", "screen_test"),
+        ],
+        routes: &[("{feature}", "")],
+        dependencies: &[],
+    },
+    BuiltinBlueprint {
+        name: "settings",
+        description: "On/off settings kept in shared_preferences by the local repository",
+        files: &[
+            shaped("application/{feature}_service.dart", "shape_service", "settings"),
+            shaped("data/{feature}_local_repository.dart", "shape_repository", "settings"),
+            shaped("data/fake_{feature}_local_repository.dart", "shape_fake_repository", "settings"),
+            DOMAIN_FILES[0],
+            DOMAIN_FILES[1],
+            DOMAIN_FILES[2],
+            file("presentation/{feature}_screen.dart", "settings_screen"),
+            file("presentation/controllers/{feature}_controller.dart", "settings_controller"),
+        ],
+        tests: &[
+            file("test_overrides.dart", "test_overrides"),
+            file("application/{feature}_service_test.dart", "service_test"),
+            file("presentation/{feature}_screen_test.dart", "screen_test"),
+        ],
+        routes: &[("{feature}", "")],
+        dependencies: &["shared_preferences"],
+    },
+];
+
+impl Blueprint {
+    /// Path of a template of a custom blueprint, `None` when the template isn't a file of its folder.
+    pub fn template_path(&self, template: &str) -> Option<String> {
+        let source = self.source.as_ref()?;
+        let path = format!("{}\\{}", source, template.replace('/', "\\"));
+        std::path::Path::new(&path).exists().then_some(path)
+    }
+
+    /// Kinds of the repositories with a fake, e.g. `["local", "remote"]`.
+    pub fn fake_kinds(&self) -> Vec<String> {
+        ["local", "remote"]
+            .iter()
+            .filter(|kind| self.files.iter().any(|file| file.path == format!("data/fake_{{feature}}_{}_repository.dart", kind)))
+            .map(|kind| kind.to_string())
+            .collect()
+    }
+}
+
+fn builtin(name: &str) -> Option<Blueprint> {
+    let files = |files: &[BuiltinFile]| -> Vec<BlueprintFile> {
+        files
+            .iter()
+            .map(|file| {
+                let mut variables = Map::new();
+                if let Some(shape) = file.shape {
+                    variables.insert("shape".to_string(), Value::from(shape));
+                }
+                BlueprintFile { path: file.path.to_string(), template: file.template.to_string(), variables }
+            })
+            .collect()
+    };
+
+    BUILTIN_BLUEPRINTS.iter().find(|blueprint| blueprint.name == name).map(|blueprint| Blueprint {
+        name: blueprint.name.to_string(),
+        description: blueprint.description.to_string(),
+        files: files(blueprint.files),
+        tests: files(blueprint.tests),
+        routes: blueprint.routes.iter().map(|(screen, path)| BlueprintRoute { screen: screen.to_string(), path: path.to_string() }).collect(),
+        dependencies: blueprint.dependencies.iter().map(|dependency| dependency.to_string()).collect(),
+        ..Blueprint::default()
+    })
+}
+
+/// Files of a custom blueprint replace the files of the same path of the blueprint it extends.
+fn merge_files(base: Vec<BlueprintFile>, files: Vec<BlueprintFile>) -> Vec<BlueprintFile> {
+    let mut merged: Vec<BlueprintFile> = base.into_iter().filter(|base| !files.iter().any(|file| file.path == base.path)).collect();
+    merged.extend(files);
+    merged
+}
+
+/// Reads .rudder/blueprints/{name}/blueprint.json, `None` when the project doesn't define the blueprint.
+fn custom(name: &str) -> Option<Result<Blueprint, String>> {
+    let source = format!("{}\\{}", BLUEPRINTS_PATH, name);
+    let manifest_path = format!("{}\\blueprint.json", source);
+    let content = std::fs::read_to_string(&manifest_path).ok()?;
+
+    let blueprint = serde_json::from_str::<Blueprint>(&content).map_err(|e| format!("Invalid {}: {}", manifest_path, e)).and_then(|blueprint| {
+        let base = match &blueprint.extends {
+            Some(extends) => builtin(extends).ok_or_else(|| format!("{} extends the unknown blueprint '{}'. Expected {}.", manifest_path, extends, builtin_names().join(", ")))?,
+            None => Blueprint::default(),
+        };
+
+        for file in blueprint.files.iter().chain(&blueprint.tests) {
+            presets::check_path(&manifest_path, &file.path)?;
+            presets::check_path(&manifest_path, &file.template)?;
+        }
+
+        Ok(Blueprint {
+            name: name.to_string(),
+            description: blueprint.description,
+            extends: blueprint.extends,
+            files: merge_files(base.files, blueprint.files),
+            tests: merge_files(base.tests, blueprint.tests),
+            routes: [base.routes, blueprint.routes].concat(),
+            dependencies: [base.dependencies, blueprint.dependencies].concat(),
+            dev_dependencies: [base.dev_dependencies, blueprint.dev_dependencies].concat(),
+            source: Some(source),
+        })
+    });
+
+    Some(blueprint)
+}
+
+/// Finds a blueprint, the project's own blueprints taking precedence over the built-in ones of the same name.
+pub fn find(name: &str) -> Result<Blueprint, String> {
+    match custom(name) {
+        Some(blueprint) => blueprint,
+        None => builtin(name).ok_or_else(|| format!("Unknown blueprint '{}'. Expected {}.", name, names().join(", "))),
+    }
+}
+
+fn builtin_names() -> Vec<&'static str> {
+    BUILTIN_BLUEPRINTS.iter().map(|blueprint| blueprint.name).collect()
+}
+
+/// Names of every blueprint, built-in first.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = builtin_names().into_iter().map(str::to_string).collect();
+
+    let mut custom_names: Vec<String> = std::fs::read_dir(BLUEPRINTS_PATH)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| std::path::Path::new(&format!("{}\\{}\\blueprint.json", BLUEPRINTS_PATH, entry.file_name().to_string_lossy())).exists())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    custom_names.sort();
+
+    names.extend(custom_names.into_iter().filter(|name| !names.contains(name)).collect::<Vec<String>>());
+    names
+}
@@ -0,0 +1,123 @@
+use clap::CommandFactory;
+use clap_complete::{generate, Shell};
+
+use crate::args::RudderArgs;
+
+/// Prints the completion script generated from the clap definitions, hooked up so that feature
+/// and sub feature names are completed from the current project through `rudder complete`.
+pub fn print_completions(shell: &str) {
+    let generator = match shell {
+        "bash" => Shell::Bash,
+        "zsh" => Shell::Zsh,
+        "fish" => Shell::Fish,
+        _ => Shell::PowerShell,
+    };
+
+    let mut script = Vec::new();
+    generate(generator, &mut RudderArgs::command(), "rudder", &mut script);
+    let script = String::from_utf8_lossy(&script).to_string();
+
+    let script = match generator {
+        // the generated function is registered through the dynamic one, which falls back to it
+        Shell::Bash => format!("{}\n{}", script.replace("complete -F _rudder ", "complete -F _rudder_dynamic "), BASH_DYNAMIC.trim()),
+        Shell::Zsh => {
+            let registration = "if [ \"$funcstack[1]\" = \"_rudder\" ]; then";
+            let registration_start = script.rfind(registration).unwrap_or(script.len());
+            let (functions, registration) = script.split_at(registration_start);
+            format!("{}{}\n\n{}", functions, ZSH_DYNAMIC.trim(), registration.replace("_rudder \"$@\"", "_rudder_dynamic \"$@\"").replace("compdef _rudder rudder", "compdef _rudder_dynamic rudder"))
+        }
+        // fish merges the candidates of every `complete` definition
+        Shell::Fish => format!("{}\n{}", script, FISH_DYNAMIC.trim()),
+        _ => format!(
+            "{}\n{}",
+            script.replace("Register-ArgumentCompleter -Native -CommandName 'rudder' -ScriptBlock {", "$rudderStaticCompleter = {"),
+            POWERSHELL_DYNAMIC.trim()
+        ),
+    };
+
+    println!("{}", script.trim_end());
+}
+
+const BASH_DYNAMIC: &str = r#"
+_rudder_dynamic() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local kind=""
+
+    case "${prev}" in
+        --features|--feature|-f)
+            kind="features"
+            ;;
+        --sub-feature|-s)
+            kind="sub-features"
+            ;;
+        model|method|exception)
+            [[ "${COMP_WORDS[1]}" == "add" ]] && kind="features"
+            ;;
+    esac
+
+    if [[ -z "${kind}" ]]; then
+        _rudder "$@"
+        return
+    fi
+
+    # complete the last name of a comma separated list
+    local prefix=""
+    [[ "${cur}" == *,* ]] && prefix="${cur%,*},"
+    COMPREPLY=($(compgen -P "${prefix}" -W "$(rudder complete ${kind} --line "${COMP_LINE}" 2>/dev/null)" -- "${cur##*,}"))
+}
+"#;
+
+const ZSH_DYNAMIC: &str = r#"
+_rudder_dynamic() {
+    local previous=${words[CURRENT-1]}
+    local kind=""
+
+    case $previous in
+        --features|--feature|-f) kind="features" ;;
+        --sub-feature|-s) kind="sub-features" ;;
+        model|method|exception) [[ ${words[2]} == add ]] && kind="features" ;;
+    esac
+
+    if [[ -z $kind ]]; then
+        _rudder "$@"
+        return
+    fi
+
+    local -a names
+    names=(${(f)"$(rudder complete $kind --line "${words[*]}" 2>/dev/null)"})
+    # complete the last name of a comma separated list
+    compset -P '*,'
+    compadd -a names
+}
+"#;
+
+const FISH_DYNAMIC: &str = r#"
+complete -c rudder -n "__fish_seen_subcommand_from add import" -s f -l features -f -a "(rudder complete features 2>/dev/null)"
+complete -c rudder -n "__fish_seen_subcommand_from add import" -l feature -f -a "(rudder complete features 2>/dev/null)"
+complete -c rudder -n "__fish_seen_subcommand_from add" -s s -l sub-feature -f -a "(rudder complete sub-features --line (commandline -p) 2>/dev/null)"
+complete -c rudder -n "__fish_seen_subcommand_from model method exception; and not __fish_seen_subcommand_from (rudder complete features 2>/dev/null)" -f -a "(rudder complete features 2>/dev/null)"
+"#;
+
+const POWERSHELL_DYNAMIC: &str = r#"
+Register-ArgumentCompleter -Native -CommandName 'rudder' -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements | ForEach-Object { $_.ToString() })
+    $previous = if ($wordToComplete) { $words[-2] } else { $words[-1] }
+
+    $kind = switch -Regex ($previous) {
+        '^(--features|--feature|-f)$' { 'features' }
+        '^(--sub-feature|-s)$' { 'sub-features' }
+        '^(model|method|exception)$' { if ($words[1] -eq 'add') { 'features' } }
+    }
+
+    if (-not $kind) {
+        return & $rudderStaticCompleter $wordToComplete $commandAst $cursorPosition
+    }
+
+    rudder complete $kind --line "$commandAst" |
+        Where-Object { $_ -like "$wordToComplete*" } |
+        ForEach-Object { [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_) }
+}
+"#;
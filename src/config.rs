@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+pub const CONFIG_PATH: &str = ".rudder\\config.json";
+
+pub const STATE_MANAGEMENTS: [&str; 2] = ["riverpod", "riverpod_freezed"];
+pub const ROUTERS: [&str; 2] = ["auto_route", "go_router"];
+
+/// States every feature gets when the project uses freezed union states.
+pub const FREEZED_STATES: &str = "initial,loading,error:Object";
+
+/// Answers of `rudder create`, saved in .rudder/config.json so later commands reuse them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    /// Preset of `rudder create`, `standard` when it isn't set.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+    pub app_title: Option<String>,
+    pub package_name: Option<String>,
+    /// `riverpod` for plain controllers, `riverpod_freezed` for controllers exposing a freezed union state.
+    pub state_management: String,
+    pub router: String,
+    pub services: Vec<String>,
+    pub features: Vec<String>,
+    pub install_dependencies: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<ThemeConfig>,
+    /// Settings of other commands, kept as they are.
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub seed: Option<String>,
+    pub mode: Option<String>,
+}
+
+impl Default for ProjectConfig {
+    fn default() -> Self {
+        ProjectConfig {
+            preset: None,
+            app_title: None,
+            package_name: None,
+            state_management: STATE_MANAGEMENTS[0].to_string(),
+            router: ROUTERS[0].to_string(),
+            services: Vec::new(),
+            features: vec!["home".to_string()],
+            install_dependencies: true,
+            theme: None,
+            other: Map::new(),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Reads .rudder/config.json, falling back to the defaults when it doesn't exist.
+    pub fn load() -> Result<ProjectConfig, String> {
+        match std::fs::read_to_string(CONFIG_PATH) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", CONFIG_PATH, e)),
+            Err(_) => Ok(ProjectConfig::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(CONFIG_PATH, format!("{}\n", content)).map_err(|e| format!("Unable to write {}: {}", CONFIG_PATH, e))
+    }
+
+    /// States given to features added without `--states`.
+    pub fn default_states(&self) -> Option<&str> {
+        if self.state_management == "riverpod_freezed" { Some(FREEZED_STATES) } else { None }
+    }
+
+    pub fn uses_go_router(&self) -> bool {
+        self.router == "go_router"
+    }
+
+    /// Checks the values that can't be typed by clap, e.g. when the config was edited by hand.
+    pub fn validate(&self) -> Result<(), String> {
+        if !STATE_MANAGEMENTS.contains(&self.state_management.as_str()) {
+            return Err(format!("Unknown state management '{}'. Expected {}.", self.state_management, STATE_MANAGEMENTS.join(" or ")));
+        }
+
+        if !ROUTERS.contains(&self.router.as_str()) {
+            return Err(format!("Unknown router '{}'. Expected {}.", self.router, ROUTERS.join(" or ")));
+        }
+
+        if let Some(package_name) = &self.package_name {
+            let valid = !package_name.is_empty()
+                && package_name.starts_with(|c: char| c.is_ascii_lowercase())
+                && package_name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            if !valid {
+                return Err(format!("Invalid package name '{}'. Use lowercase letters, digits and underscores.", package_name));
+            }
+        }
+
+        Ok(())
+    }
+}
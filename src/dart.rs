@@ -1,4 +1,9 @@
-#![allow(dead_code, unused)]
+#![allow(dead_code, unused, clippy::needless_return)]
+
+use crate::env::EnvEntry;
+use crate::model::{camel_case, DartModel, UnionState};
+use crate::theme::PaletteColor;
+use crate::tokens::{dart_number, size_name, ColorToken, TextStyleToken};
 
 pub trait StringTrait {
   fn capitalize(self) -> String;
@@ -42,6 +47,8 @@ pub fn main_dart() -> String {
         import 'package:flutter_dotenv/flutter_dotenv.dart';
 
         import 'src/app.dart';
+        import 'src/exceptions/async_errors.dart';
+        import 'src/fake_overrides.dart';
         import 'src/services/logger_service.dart';
 
         void main() async {
@@ -50,6 +57,7 @@ pub fn main_dart() -> String {
             await dotenv.load(fileName: '.env');
 
             final container = ProviderContainer(overrides: [
+                if (useFakes) ...fakeOverrides,
                 loggerServiceProvider.overrideWith((ref) => LoggerService(ref)),
             ], observers: [
                 AsyncErrorLogger()
@@ -104,50 +112,85 @@ pub fn main_dart() -> String {
 }
 
 // app.dart
-pub fn app_dart() -> String {
-    return "
+pub fn app_dart(app_title: &str) -> String {
+    let app_title = app_title.replace('\\', "\\\\").replace('\'', "\\'");
+    return format!(
+        "
         import 'package:flutter/material.dart';
         import 'package:flutter_riverpod/flutter_riverpod.dart';
         import 'package:responsive_framework/responsive_framework.dart';
 
-        import 'utils/routes.dart';
+        import 'routes/routes.dart';
+        import 'utils/theme.dart';
 
-        class MyApp extends ConsumerStatefulWidget {
-            const MyApp({super.key});
+        class MyApp extends ConsumerStatefulWidget {{
+            const MyApp({{super.key}});
 
             @override
             ConsumerState<MyApp> createState() => _MyAppState();
-        }
+        }}
 
-        class _MyAppState extends ConsumerState<MyApp> {
+        class _MyAppState extends ConsumerState<MyApp> {{
             late final AppRouter _appRouter;
 
             @override
-            void initState() {
+            void initState() {{
                 _appRouter = AppRouter(ref);
                 super.initState();
-            }
+            }}
 
             @override
-            Widget build(BuildContext context) {
+            Widget build(BuildContext context) {{
                 return SafeArea(
                     child: MaterialApp.router(
-                    title: 'Flutter Demo',
+                    title: '{app_title}',
                     debugShowCheckedModeBanner: false,
-                    theme: ThemeData(
-                        useMaterial3: true,
-                        appBarTheme: const AppBarTheme(backgroundColor: Color.fromARGB(255, 80, 66, 105), centerTitle: true, toolbarHeight: 40, foregroundColor: Colors.white),
-                        elevatedButtonTheme: ElevatedButtonThemeData(
-                        style: ElevatedButton.styleFrom(
-                            foregroundColor: Colors.white,
-                            backgroundColor: Colors.grey,
-                        ),
-                        ),
-                        inputDecorationTheme: const InputDecorationTheme(
-                        errorStyle: TextStyle(color: Colors.red, fontSize: 12.0, fontWeight: FontWeight.bold, fontStyle: FontStyle.italic),
+                    theme: AppTheme.light,
+                    darkTheme: AppTheme.dark,
+                    themeMode: ref.watch(themeModeProvider),
+                    routerConfig: _appRouter.config(navigatorObservers: () => [MyRouteObserver()]),
+                    builder: (context, child) => ResponsiveBreakpoints.builder(
+                            child: child!,
+                            breakpoints: [
+                            const Breakpoint(start: 0, end: 450, name: MOBILE),
+                            const Breakpoint(start: 451, end: 800, name: TABLET),
+                            const Breakpoint(start: 801, end: 1920, name: DESKTOP),
+                            const Breakpoint(start: 1921, end: double.infinity, name: '4K'),
+                            ],
                         ),
                     ),
-                    routerConfig: _appRouter.config(navigatorObservers: () => [MyRouteObserver()]),
+                );
+            }}
+        }}
+    "
+    ).trim().to_string();
+}
+
+// app.dart using go_router
+pub fn go_router_app_dart(app_title: &str) -> String {
+    let app_title = app_title.replace('\\', "\\\\").replace('\'', "\\'");
+    return format!(
+        "
+        import 'package:flutter/material.dart';
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+        import 'package:responsive_framework/responsive_framework.dart';
+
+        import 'routes/routes.dart';
+        import 'utils/theme.dart';
+
+        class MyApp extends ConsumerWidget {{
+            const MyApp({{super.key}});
+
+            @override
+            Widget build(BuildContext context, WidgetRef ref) {{
+                return SafeArea(
+                    child: MaterialApp.router(
+                    title: '{app_title}',
+                    debugShowCheckedModeBanner: false,
+                    theme: AppTheme.light,
+                    darkTheme: AppTheme.dark,
+                    themeMode: ref.watch(themeModeProvider),
+                    routerConfig: ref.watch(goRouterProvider),
                     builder: (context, child) => ResponsiveBreakpoints.builder(
                             child: child!,
                             breakpoints: [
@@ -159,9 +202,10 @@ pub fn app_dart() -> String {
                         ),
                     ),
                 );
-            }
-        }
-    ".trim().to_string();
+            }}
+        }}
+    "
+    ).trim().to_string();
 }
 
 // routes.dart
@@ -289,6 +333,25 @@ pub fn routes_dart() -> String {
     ".trim().to_string();
 }
 
+// routes.dart using go_router
+pub fn go_router_dart(initial_location: &str) -> String {
+    return format!(
+        "
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+        import 'package:go_router/go_router.dart';
+
+        /// Routes of the app. `rudder add` registers the screen of each new feature.
+        final goRouterProvider = Provider<GoRouter>((ref) {{
+            return GoRouter(
+                initialLocation: '{initial_location}',
+                routes: [
+                ],
+            );
+        }});
+        "
+    ).trim().to_string();
+}
+
 // extensions.dart
 pub fn extensions_dart() -> String {
     return "
@@ -301,15 +364,14 @@ pub fn extensions_dart() -> String {
 }
 
 // statless flutter widget
-pub fn stateless_widget(feature_name: &str) -> String {
+pub fn stateless_widget(feature_name: &str, auto_route: bool) -> String {
+    let (router_import, route_page) = route_page_annotation(auto_route);
     let feature_name = feature_name.to_string().to_pascal_case().capitalize();
     return format!(
         "
-        import 'package:flutter/material.dart';
-        import 'package:auto_route/auto_route.dart';
+        import 'package:flutter/material.dart';{router_import}
 
-        @RoutePage()
-        class {feature_name}Screen extends StatelessWidget {{
+        {route_page}class {feature_name}Screen extends StatelessWidget {{
             const {feature_name}Screen({{Key? key}}) : super(key: key);
 
             @override
@@ -328,6 +390,50 @@ pub fn stateless_widget(feature_name: &str) -> String {
     ).trim().to_string();
     }
 
+// statless flutter widget reading its strings from the ARB files
+pub fn localized_stateless_widget(feature_name: &str, l10n_import: &str, auto_route: bool) -> String {
+    let (router_import, route_page) = route_page_annotation(auto_route);
+    let title_key = l10n_title_key(feature_name);
+    let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+    return format!(
+        "
+        import 'package:flutter/material.dart';{router_import}
+
+        import '{l10n_import}';
+
+        {route_page}class {feature_name}Screen extends StatelessWidget {{
+            const {feature_name}Screen({{Key? key}}) : super(key: key);
+
+            @override
+            Widget build(BuildContext context) {{
+                return Scaffold(
+                    appBar: AppBar(
+                        title: Text(context.l10n.{title_key}),
+                    ),
+                    body: Center(
+                        child: Text(context.l10n.{title_key}),
+                    ),
+                );
+            }}
+        }}
+        "
+    ).trim().to_string();
+    }
+
+/// Import and annotation making a screen an auto_route page, empty with go_router.
+fn route_page_annotation(auto_route: bool) -> (&'static str, &'static str) {
+    if auto_route {
+        ("\n        import 'package:auto_route/auto_route.dart';", "@RoutePage()\n        ")
+    } else {
+        ("", "")
+    }
+}
+
+/// ARB key of a feature screen's title, e.g. `ordersTitle`.
+pub fn l10n_title_key(feature_name: &str) -> String {
+    format!("{}Title", camel_case(feature_name))
+}
+
 // stateful flutter widget
 // pub fn stateful_widget(feature_name: &str) -> String {
 //     let feature_name = feature_name.to_string().to_pascal_case().capitalize();
@@ -381,7 +487,7 @@ pub fn local_repository(feature_name: &str) -> String {
             Future<void> save() async => throw UnimplementedError();
         }}
 
-        final {}LocalRepositoryProvider = Provider<{feature_name}LocalRepository>((ref) => {feature_name}LocalRepository(ref));
+        final {}LocalRepositoryProvider = Provider<Abstract{feature_name}LocalRepository>((ref) => {feature_name}LocalRepository(ref));
         
         ",
         feature_name.to_lowercase()
@@ -408,7 +514,7 @@ pub fn remote_repository(feature_name: &str) -> String {
             Future<void> save() async => throw UnimplementedError();
         }}
 
-        final {}RemoteRepositoryProvider = Provider<{feature_name}RemoteRepository>((ref) => {feature_name}RemoteRepository(ref));
+        final {}RemoteRepositoryProvider = Provider<Abstract{feature_name}RemoteRepository>((ref) => {feature_name}RemoteRepository(ref));
         
         ",
         feature_name.to_lowercase()
@@ -417,52 +523,49 @@ pub fn remote_repository(feature_name: &str) -> String {
 
 // local repository
 pub fn fake_local_repository(feature_name: &str) -> String {
+    let file_name = feature_name;
     let feature_name = feature_name.to_string().to_pascal_case().capitalize();
     return format!(
         "
         import 'package:flutter_riverpod/flutter_riverpod.dart';
 
-        abstract class AbstractFake{feature_name}LocalRepository {{
-            Future<void> save();
-        }}
+        import '{file_name}_local_repository.dart';
 
-        class Fake{feature_name}LocalRepository implements AbstractFake{feature_name}LocalRepository{{
+        class Fake{feature_name}LocalRepository implements Abstract{feature_name}LocalRepository {{
             final Ref ref;
 
             Fake{feature_name}LocalRepository(this.ref);
 
             @override
-            Future<void> save() async => throw UnimplementedError();
+            Future<void> save() async {{}}
         }}
 
-        final fake{feature_name}LocalRepositoryProvider = Provider<Fake{feature_name}LocalRepository>((ref) => Fake{feature_name}LocalRepository(ref));
+        final fake{feature_name}LocalRepositoryProvider = Provider<Abstract{feature_name}LocalRepository>((ref) => Fake{feature_name}LocalRepository(ref));
         
         "
-        
     ).trim().to_string();
     }
 
 // remote repository
 pub fn fake_remote_repository(feature_name: &str) -> String {
+    let file_name = feature_name;
     let feature_name = feature_name.to_string().to_pascal_case().capitalize();
     return format!(
         "
         import 'package:flutter_riverpod/flutter_riverpod.dart';
 
-        abstract class AbstractFake{feature_name}RemoteRepository {{
-            Future<void> save();
-        }}
+        import '{file_name}_remote_repository.dart';
 
-        class Fake{feature_name}RemoteRepository implements AbstractFake{feature_name}RemoteRepository {{
+        class Fake{feature_name}RemoteRepository implements Abstract{feature_name}RemoteRepository {{
             final Ref ref;
 
             Fake{feature_name}RemoteRepository(this.ref);
 
             @override
-            Future<void> save() async => throw UnimplementedError();
+            Future<void> save() async {{}}
         }}
 
-        final fake{feature_name}RemoteRepositoryProvider = Provider<Fake{feature_name}RemoteRepository>((ref) => Fake{feature_name}RemoteRepository(ref));
+        final fake{feature_name}RemoteRepositoryProvider = Provider<Abstract{feature_name}RemoteRepository>((ref) => Fake{feature_name}RemoteRepository(ref));
         
         "
     ).trim().to_string();
@@ -578,9 +681,11 @@ pub fn logger_service() -> String {
     // async_errors.dart
     pub fn async_errors() -> String {
         return "
-            import '../app_exceptions.dart';
             import 'package:flutter_riverpod/flutter_riverpod.dart';
 
+            import '../services/logger_service.dart';
+            import 'app_exceptions.dart';
+
             class AsyncErrorLogger extends ProviderObserver {
 
                 @override
@@ -622,8 +727,8 @@ pub fn logger_service() -> String {
             part 'app_exceptions.freezed.dart';
 
             @freezed
-            class AppException with _$AppException {
-                const factory AppException.invalidUsernamePassword(Object e) = InvalidUsernamePassword;
+            sealed class AppException with _$AppException implements Exception {
+                const factory AppException.invalidUsernamePassword() = InvalidUsernamePassword;
             }
 
             extension AppExceptionMessages on AppException {
@@ -679,15 +784,24 @@ pub fn logger_service() -> String {
     }
 
     // app_colors.dart
-    pub fn app_colors() -> String {
-        return "
+    pub fn app_colors(palette: &[PaletteColor]) -> String {
+        let colors = palette.iter().map(|color| {
+            format!("                /// {}\n                static const {} = {};", color.description, color.name, color.color.to_dart())
+        }).collect::<Vec<String>>().join("\n\n");
+
+        return format!(
+            "
             import 'package:flutter/material.dart';
-            
+
             /// App colors
-            abstract class AppColors {
+            /// Generated from the seed color, regenerate with: rudder theme --seed <color>
+            abstract class AppColors {{
+{colors}
+
                 static const darkBlue = Color(0xFF555E82);
-            }
-        ".trim().to_string();
+            }}
+            "
+        ).trim().to_string();
     }
 
     // app_text_styles.dart
@@ -695,6 +809,8 @@ pub fn logger_service() -> String {
         return "
             import 'package:flutter/material.dart';
 
+            import 'app_colors.dart';
+
             /// App TextStyles
             abstract class AppTextStyles {
                 // Body styles /////////////////////////////////////////
@@ -749,4 +865,1427 @@ pub fn logger_service() -> String {
                 }
             }
         ".trim().to_string();
-    }
\ No newline at end of file
+    }
+
+    // models file header
+    pub fn models_header(feature_name: &str) -> String {
+        return format!(
+            "
+            import 'package:freezed_annotation/freezed_annotation.dart';
+
+            part '{feature_name}_models.freezed.dart';
+            part '{feature_name}_models.g.dart';
+            "
+        ).trim().to_string();
+    }
+
+    // freezed model
+    pub fn freezed_model(model: &DartModel) -> String {
+        let name = &model.name;
+        let fields = model.fields.iter().map(|field| {
+            let json_key = match &field.json_key {
+                Some(key) => format!("@JsonKey(name: '{key}') "),
+                None => String::new(),
+            };
+            let required = if field.is_nullable() { "" } else { "required " };
+            format!("                    {json_key}{required}{} {},", field.dart_type, field.name)
+        }).collect::<Vec<String>>().join("\n");
+
+        return format!(
+            "
+            @freezed
+            abstract class {name} with _${name} {{
+                const factory {name}({{
+{fields}
+                }}) = _{name};
+
+                factory {name}.fromJson(Map<String, dynamic> json) => _${name}FromJson(json);
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // remote repository generated from an OpenAPI spec
+    pub fn api_remote_repository(feature_name: &str, signatures: &[String], implementations: &[String]) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let signatures = signatures.iter().map(|signature| format!("                {signature};")).collect::<Vec<String>>().join("\n");
+        let implementations = implementations.join("\n\n");
+        return format!(
+            "
+            import 'package:dio/dio.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../../services/http_client_service.dart';
+            import '../domain/{file_name}_models.dart';
+
+            abstract class Abstract{feature_name}RemoteRepository {{
+{signatures}
+            }}
+
+            class {feature_name}RemoteRepository implements Abstract{feature_name}RemoteRepository {{
+                final Ref ref;
+
+                {feature_name}RemoteRepository(this.ref);
+
+                Dio get _dio => ref.read(httpClientServiceProvider).dio;
+
+{implementations}
+            }}
+
+            final {}RemoteRepositoryProvider = Provider<Abstract{feature_name}RemoteRepository>((ref) => {feature_name}RemoteRepository(ref));
+            ",
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // fake remote repository generated from an OpenAPI spec
+    pub fn api_fake_remote_repository(feature_name: &str, implementations: &[String]) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let implementations = implementations.join("\n\n");
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../domain/{file_name}_models.dart';
+            import '{file_name}_remote_repository.dart';
+
+            class Fake{feature_name}RemoteRepository implements Abstract{feature_name}RemoteRepository {{
+                final Ref ref;
+
+                Fake{feature_name}RemoteRepository(this.ref);
+
+{implementations}
+            }}
+
+            final fake{feature_name}RemoteRepositoryProvider = Provider<Abstract{feature_name}RemoteRepository>((ref) => Fake{feature_name}RemoteRepository(ref));
+            "
+        ).trim().to_string();
+    }
+
+    // http_client_service.dart
+    pub fn http_client_service() -> String {
+        return "
+            import 'package:dio/dio.dart';
+            import 'package:flutter_dotenv/flutter_dotenv.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            final httpClientServiceProvider = Provider<HttpClientService>((ref) => throw UnimplementedError());
+
+            class HttpClientService {
+                final Ref ref;
+                final Dio dio;
+
+                HttpClientService(this.ref) : dio = Dio(BaseOptions(baseUrl: dotenv.env['API_BASE_URL'] ?? ''));
+
+                Future<HttpClientService> init() async {
+                    return this;
+                }
+            }
+        ".trim().to_string();
+    }
+
+    // freezed union of a feature's UI states
+    pub fn union_states(feature_name: &str, states: &[UnionState]) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let factories = states.iter().map(|state| {
+            let parameter = match &state.payload {
+                Some(payload) => format!("{payload} {}", state.payload_name()),
+                None => String::new(),
+            };
+            format!("                const factory {feature_name}State.{}({parameter}) = _{};", state.name, state.name.clone().capitalize())
+        }).collect::<Vec<String>>().join("\n");
+
+        return format!(
+            "
+            import 'package:freezed_annotation/freezed_annotation.dart';
+
+            import '{file_name}_models.dart';
+
+            part '{file_name}_unions.freezed.dart';
+
+            @freezed
+            sealed class {feature_name}State with _${feature_name}State {{
+{factories}
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // controller exposing a feature's union state
+    pub fn state_controller(feature_name: &str, initial_state: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../domain/{file_name}_unions.dart';
+
+            class {feature_name}Controller extends StateNotifier<{feature_name}State> {{
+                final Ref ref;
+
+                {feature_name}Controller(this.ref) : super(const {feature_name}State.{initial_state}());
+
+                Future<void> fetchSomething() async => throw UnimplementedError();
+            }}
+
+            final {}ControllerProvider = StateNotifierProvider.autoDispose<{feature_name}Controller, {feature_name}State>((ref) => {feature_name}Controller(ref));
+            ",
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // {feature}_exceptions.dart
+    pub fn feature_exceptions(feature_name: &str, exceptions_import: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        return format!(
+            "
+            import '{exceptions_import}';
+
+            /// AppException cases raised by the {file_name} feature.
+            /// Add one with: rudder add exception {file_name} <name> \"<message>\"
+            abstract class {feature_name}Exceptions {{
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // fake repository overrides shared by a feature's tests, of its local and remote repositories unless `kinds` tells
+    pub fn test_overrides(package_name: &str, feature_import: &str, feature_name: &str, kinds: &[String]) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        let kinds: Vec<String> = if kinds.is_empty() { vec!["local".to_string(), "remote".to_string()] } else { kinds.to_vec() };
+        let fake_imports = kinds.iter().map(|kind| format!("\n            import 'package:{package_name}/{feature_import}/data/fake_{file_name}_{kind}_repository.dart';")).collect::<String>();
+        let imports = kinds.iter().map(|kind| format!("\n            import 'package:{package_name}/{feature_import}/data/{file_name}_{kind}_repository.dart';")).collect::<String>();
+        let overrides = kinds
+            .iter()
+            .map(|kind| {
+                let kind = kind.to_string().capitalize();
+                format!("\n                {provider}{kind}RepositoryProvider.overrideWith((ref) => Fake{feature_name}{kind}Repository(ref)),")
+            })
+            .collect::<String>();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';{fake_imports}{imports}
+
+            /// Overrides the {file_name} repositories with fakes.
+            List<Override> {provider}Overrides() => [{overrides}
+            ];
+            "
+        ).trim().to_string();
+    }
+
+    // application service test
+    pub fn service_test(package_name: &str, feature_import: &str, feature_name: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:flutter_test/flutter_test.dart';
+            import 'package:{package_name}/{feature_import}/application/{file_name}_service.dart';
+
+            import '../test_overrides.dart';
+
+            void main() {{
+                late ProviderContainer container;
+
+                setUp(() => container = ProviderContainer(overrides: {}Overrides()));
+
+                tearDown(() => container.dispose());
+
+                test('provides a {feature_name}Service', () {{
+                    expect(container.read({}ServiceProvider), isA<{feature_name}Service>());
+                }});
+            }}
+            ",
+            feature_name.to_lowercase(),
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // controller test
+    pub fn controller_test(package_name: &str, feature_import: &str, feature_name: &str, initial_state: Option<&str>) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let (unions_import, expected) = match initial_state {
+            Some(initial_state) => (
+                format!("\n            import 'package:{package_name}/{feature_import}/domain/{file_name}_unions.dart';"),
+                format!("const {feature_name}State.{initial_state}()"),
+            ),
+            None => (String::new(), "isTrue".to_string()),
+        };
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:flutter_test/flutter_test.dart';
+            import 'package:{package_name}/{feature_import}/presentation/controllers/{file_name}_controller.dart';{unions_import}
+
+            import '../../test_overrides.dart';
+
+            void main() {{
+                late ProviderContainer container;
+
+                setUp(() => container = ProviderContainer(overrides: {}Overrides()));
+
+                tearDown(() => container.dispose());
+
+                test('starts in its initial state', () {{
+                    expect(container.read({}ControllerProvider), {expected});
+                }});
+            }}
+            ",
+            feature_name.to_lowercase(),
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // screen widget test
+    pub fn screen_test(package_name: &str, feature_import: &str, feature_name: &str, localized: bool) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let (l10n_import, delegates) = if localized {
+            (format!("\n            import 'package:{package_name}/src/l10n/l10n.dart';"), "localizationsDelegates: AppLocalizations.localizationsDelegates, ")
+        } else {
+            (String::new(), "")
+        };
+        return format!(
+            "
+            import 'package:flutter/material.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:flutter_test/flutter_test.dart';
+            import 'package:{package_name}/{feature_import}/presentation/{file_name}_screen.dart';{l10n_import}
+
+            import '../test_overrides.dart';
+
+            void main() {{
+                testWidgets('{feature_name}Screen shows its title', (tester) async {{
+                    await tester.pumpWidget(ProviderScope(overrides: {}Overrides(), child: const MaterialApp({delegates}home: {feature_name}Screen())));
+
+                    expect(find.text('{feature_name}'), findsWidgets);
+                }});
+            }}
+            ",
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // fake_overrides.dart
+    pub fn fake_overrides_dart() -> String {
+        return "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            /// Run the app with --dart-define=USE_FAKES=true to use the fake repositories of every feature.
+            const useFakes = bool.fromEnvironment('USE_FAKES');
+
+            /// Overrides the repository providers of every feature with their fakes.
+            final fakeOverrides = <Override>[
+            ];
+        ".trim().to_string();
+    }
+
+    // secure_storage_service.dart
+    pub fn secure_storage_service() -> String {
+        return "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:flutter_secure_storage/flutter_secure_storage.dart';
+
+            final secureStorageServiceProvider = Provider<SecureStorageService>((ref) => throw UnimplementedError());
+
+            class SecureStorageService {
+                final Ref ref;
+                final FlutterSecureStorage _storage = const FlutterSecureStorage();
+
+                SecureStorageService(this.ref);
+
+                Future<SecureStorageService> init() async {
+                    return this;
+                }
+
+                Future<String?> read(String key) => _storage.read(key: key);
+
+                Future<void> write(String key, String value) => _storage.write(key: key, value: value);
+
+                Future<void> delete(String key) => _storage.delete(key: key);
+
+                Future<void> clear() => _storage.deleteAll();
+            }
+        ".trim().to_string();
+    }
+
+    // shared_preferences_service.dart
+    pub fn shared_preferences_service() -> String {
+        return "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:shared_preferences/shared_preferences.dart';
+
+            final sharedPreferencesServiceProvider = Provider<SharedPreferencesService>((ref) => throw UnimplementedError());
+
+            class SharedPreferencesService {
+                final Ref ref;
+                late final SharedPreferences _preferences;
+
+                SharedPreferencesService(this.ref);
+
+                Future<SharedPreferencesService> init() async {
+                    _preferences = await SharedPreferences.getInstance();
+                    return this;
+                }
+
+                String? getString(String key) => _preferences.getString(key);
+
+                Future<bool> setString(String key, String value) => _preferences.setString(key, value);
+
+                bool? getBool(String key) => _preferences.getBool(key);
+
+                Future<bool> setBool(String key, bool value) => _preferences.setBool(key, value);
+
+                Future<bool> remove(String key) => _preferences.remove(key);
+            }
+        ".trim().to_string();
+    }
+
+    // connectivity_service.dart
+    pub fn connectivity_service() -> String {
+        return "
+            import 'package:connectivity_plus/connectivity_plus.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            final connectivityServiceProvider = Provider<ConnectivityService>((ref) => throw UnimplementedError());
+
+            class ConnectivityService {
+                final Ref ref;
+                final Connectivity _connectivity = Connectivity();
+
+                ConnectivityService(this.ref);
+
+                Future<ConnectivityService> init() async {
+                    return this;
+                }
+
+                Future<bool> isConnected() async {
+                    final results = await _connectivity.checkConnectivity();
+                    return !results.contains(ConnectivityResult.none);
+                }
+
+                Stream<bool> get onConnectivityChanged => _connectivity.onConnectivityChanged.map((results) => !results.contains(ConnectivityResult.none));
+            }
+        ".trim().to_string();
+    }
+
+    // biometrics_service.dart
+    pub fn biometrics_service() -> String {
+        return "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+            import 'package:local_auth/local_auth.dart';
+
+            final biometricsServiceProvider = Provider<BiometricsService>((ref) => throw UnimplementedError());
+
+            class BiometricsService {
+                final Ref ref;
+                final LocalAuthentication _auth = LocalAuthentication();
+
+                BiometricsService(this.ref);
+
+                Future<BiometricsService> init() async {
+                    return this;
+                }
+
+                Future<bool> isAvailable() async => await _auth.canCheckBiometrics && await _auth.isDeviceSupported();
+
+                Future<bool> authenticate(String reason) => _auth.authenticate(localizedReason: reason);
+            }
+        ".trim().to_string();
+    }
+
+    // analytics_service.dart
+    pub fn analytics_service() -> String {
+        return "
+            import 'package:firebase_analytics/firebase_analytics.dart';
+            import 'package:firebase_core/firebase_core.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            final analyticsServiceProvider = Provider<AnalyticsService>((ref) => throw UnimplementedError());
+
+            class AnalyticsService {
+                final Ref ref;
+
+                AnalyticsService(this.ref);
+
+                // read once Firebase is initialized
+                FirebaseAnalytics get _analytics => FirebaseAnalytics.instance;
+
+                Future<AnalyticsService> init() async {
+                    if (Firebase.apps.isEmpty) {
+                        await Firebase.initializeApp();
+                    }
+                    return this;
+                }
+
+                Future<void> logEvent(String name, {Map<String, Object>? parameters}) => _analytics.logEvent(name: name, parameters: parameters);
+
+                Future<void> logScreenView(String screenName) => _analytics.logScreenView(screenName: screenName);
+
+                Future<void> setUserId(String? id) => _analytics.setUserId(id: id);
+            }
+        ".trim().to_string();
+    }
+
+    // blank service
+    pub fn blank_service(service_name: &str) -> String {
+        let class_name = format!("{}Service", service_name.to_string().to_pascal_case());
+        let provider_name = format!("{}{}Provider", class_name[..1].to_lowercase(), &class_name[1..]);
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            final {provider_name} = Provider<{class_name}>((ref) => throw UnimplementedError());
+
+            class {class_name} {{
+                final Ref ref;
+
+                {class_name}(this.ref);
+
+                Future<{class_name}> init() async {{
+                    return this;
+                }}
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // constants/env.dart
+    pub fn env_dart(entries: &[EnvEntry]) -> String {
+        let getters = entries.iter().map(|entry| {
+            let (dart_type, reader) = match entry.env_type.as_str() {
+                "int" => ("int", "_int"),
+                "bool" => ("bool", "_bool"),
+                "url" => ("Uri", "_url"),
+                _ => ("String", "_string"),
+            };
+            let default = match (&entry.default, entry.env_type.as_str()) {
+                (Some(default), "int") | (Some(default), "bool") => format!(", {default}"),
+                (Some(default), _) => format!(", '{}'", default.replace('\\', "\\\\").replace('\'', "\\'")),
+                (None, _) => String::new(),
+            };
+            format!("                static {dart_type} get {} => {reader}('{}'{default});", camel_case(&entry.key.to_lowercase()), entry.key)
+        }).collect::<Vec<String>>().join("\n");
+
+        let validations = entries.iter().map(|entry| {
+            format!("                    {};", camel_case(&entry.key.to_lowercase()))
+        }).collect::<Vec<String>>().join("\n");
+
+        return format!(
+            "
+            import 'package:flutter_dotenv/flutter_dotenv.dart';
+
+            /// Typed access to the .env file.
+            /// Generated from the keys of .env, regenerate with: rudder env sync
+            abstract class Env {{
+{getters}
+
+                /// Reads every value once so that a missing or malformed value fails at startup.
+                static void validate() {{
+{validations}
+                }}
+
+                static String _string(String key, [String? fallback]) {{
+                    final value = dotenv.env[key];
+                    if (value == null || value.isEmpty) {{
+                        if (fallback != null) return fallback;
+                        throw StateError('Missing $key in .env');
+                    }}
+                    return value;
+                }}
+
+                static int _int(String key, [int? fallback]) {{
+                    final value = int.tryParse(_string(key, fallback?.toString()));
+                    if (value == null) throw StateError('$key in .env is not an int');
+                    return value;
+                }}
+
+                static bool _bool(String key, [bool? fallback]) {{
+                    switch (_string(key, fallback?.toString()).toLowerCase()) {{
+                        case 'true':
+                            return true;
+                        case 'false':
+                            return false;
+                        default:
+                            throw StateError('$key in .env is not a bool');
+                    }}
+                }}
+
+                static Uri _url(String key, [String? fallback]) {{
+                    final value = Uri.tryParse(_string(key, fallback));
+                    if (value == null || !value.hasScheme) throw StateError('$key in .env is not a url');
+                    return value;
+                }}
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    pub fn flavor_dart(flavors: &[String]) -> String {
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            /// Build flavors of the app, each started from its own lib/main_<flavor>.dart
+            /// and configured by its own .env.<flavor> file.
+            enum Flavor {{ {} }}
+
+            /// Flavor the app was started with, overridden in bootstrap().
+            final flavorProvider = Provider<Flavor>((ref) => throw UnimplementedError());
+            ",
+            flavors.join(", ")
+        ).trim().to_string();
+    }
+
+    pub fn flavor_main_dart(flavor: &str) -> String {
+        return format!(
+            "
+            import 'src/bootstrap.dart';
+            import 'src/constants/flavor.dart';
+
+            void main() => bootstrap(Flavor.{flavor});
+            "
+        ).trim().to_string();
+    }
+
+    // l10n.yaml
+    pub fn l10n_yaml(template_locale: &str) -> String {
+        return format!(
+            "
+arb-dir: lib/src/l10n
+template-arb-file: app_{template_locale}.arb
+output-dir: lib/src/l10n/generated
+output-localization-file: app_localizations.dart
+synthetic-package: false
+nullable-getter: false
+            "
+        ).trim().to_string();
+    }
+
+    // l10n.dart
+    pub fn l10n_dart() -> String {
+        return "
+            import 'package:flutter/widgets.dart';
+
+            import 'generated/app_localizations.dart';
+
+            export 'generated/app_localizations.dart';
+
+            extension AppLocalizationsContext on BuildContext {
+                /// Strings of the current locale, generated from the ARB files by `flutter gen-l10n`.
+                AppLocalizations get l10n => AppLocalizations.of(this);
+            }
+        ".trim().to_string();
+    }
+
+    // theme.dart
+    pub fn theme_dart(theme_mode: &str) -> String {
+        return format!(
+            "
+            import 'package:flutter/material.dart';
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../constants/app_colors.dart';
+
+            /// Light and dark themes of the app, built from the AppColors palette.
+            abstract class AppTheme {{
+                static ThemeData get light => _theme(Brightness.light);
+                static ThemeData get dark => _theme(Brightness.dark);
+
+                static ThemeData _theme(Brightness brightness) {{
+                    final isDark = brightness == Brightness.dark;
+                    final colorScheme = ColorScheme.fromSeed(
+                        seedColor: AppColors.seed,
+                        brightness: brightness,
+                        primary: isDark ? AppColors.kPrimaryDark : AppColors.kPrimary,
+                        error: isDark ? AppColors.kErrorDark : AppColors.kError,
+                    );
+                    final textColor = isDark ? AppColors.kTextDark : AppColors.kText;
+
+                    return ThemeData(
+                        useMaterial3: true,
+                        brightness: brightness,
+                        colorScheme: colorScheme,
+                        scaffoldBackgroundColor: isDark ? AppColors.kBackgroundDark : AppColors.kBackground,
+                        textTheme: ThemeData(brightness: brightness).textTheme.apply(bodyColor: textColor, displayColor: textColor),
+                        appBarTheme: AppBarTheme(backgroundColor: colorScheme.primary, foregroundColor: colorScheme.onPrimary, centerTitle: true, toolbarHeight: 40),
+                        elevatedButtonTheme: ElevatedButtonThemeData(
+                            style: ElevatedButton.styleFrom(
+                                foregroundColor: colorScheme.onPrimary,
+                                backgroundColor: colorScheme.primary,
+                            ),
+                        ),
+                        inputDecorationTheme: InputDecorationTheme(
+                            errorStyle: TextStyle(color: colorScheme.error, fontSize: 12.0, fontWeight: FontWeight.bold, fontStyle: FontStyle.italic),
+                        ),
+                    );
+                }}
+            }}
+
+            /// Theme mode of the app, switch it with: ref.read(themeModeProvider.notifier).state = ThemeMode.dark
+            final themeModeProvider = StateProvider<ThemeMode>((ref) => ThemeMode.{theme_mode});
+            "
+        ).trim().to_string();
+    }
+
+    // app_colors.dart generated from design tokens
+    // the seed palette is kept for the colors the tokens don't define, theme.dart reads them
+    pub fn token_colors(colors: &[ColorToken], palette: &[PaletteColor]) -> String {
+        let palette = palette.iter().filter(|color| !colors.iter().any(|token| token.name == color.name)).map(|color| {
+            format!("                /// {}\n                static const {} = {};", color.description, color.name, color.color.to_dart())
+        }).collect::<Vec<String>>().join("\n\n");
+
+        let colors = colors.iter().map(|color| {
+            format!("                static const {} = {};", color.name, color.color)
+        }).collect::<Vec<String>>().join("\n");
+
+        let colors = if palette.is_empty() { colors } else { format!("{}\n\n{}", palette, colors) };
+
+        return format!(
+            "
+            import 'package:flutter/material.dart';
+
+            /// App colors
+            /// Generated from the design tokens, regenerate with: rudder tokens import <file>
+            abstract class AppColors {{
+{colors}
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // app_sizes.dart generated from design tokens
+    pub fn token_sizes(sizes: &[f64]) -> String {
+        let constants = sizes.iter().map(|size| format!("            static const {} = {};", size_name(*size), dart_number(*size))).collect::<Vec<String>>().join("\n");
+        // a SizedBox can't be negative, so negative sizes get no gap
+        let gaps = sizes.iter().filter(|size| **size >= 0.0);
+        let gaps_w = gaps.clone().map(|size| format!("            const gapW{name} = SizedBox(width: Sizes.p{name});", name = &size_name(*size)[1..])).collect::<Vec<String>>().join("\n");
+        let gaps_h = gaps.map(|size| format!("            const gapH{name} = SizedBox(height: Sizes.p{name});", name = &size_name(*size)[1..])).collect::<Vec<String>>().join("\n");
+
+        return format!(
+            "
+            import 'package:flutter/material.dart';
+
+            /// Constant sizes to be used in the app (paddings, gaps, rounded corners etc.)
+            /// Generated from the design tokens, regenerate with: rudder tokens import <file>
+            class Sizes {{
+{constants}
+            }}
+
+            /// Constant gap widths
+{gaps_w}
+
+            /// Constant gap heights
+{gaps_h}
+            "
+        ).trim().to_string();
+    }
+
+    // app_text_styles.dart generated from design tokens
+    pub fn token_text_styles(styles: &[TextStyleToken], text_color: Option<&str>) -> String {
+        let styles = styles.iter().map(|style| {
+            let mut properties = Vec::new();
+            let mut description = Vec::new();
+
+            if let Some(font_family) = &style.font_family {
+                properties.push(format!("fontFamily: '{}'", font_family.replace('\'', "\\'")));
+            }
+            if let Some(font_size) = style.font_size {
+                properties.push(format!("fontSize: {}", dart_number(font_size)));
+                description.push(format!("fontSize: {}", dart_number(font_size)));
+            }
+            if let Some(color) = text_color {
+                properties.push(format!("color: {}", color));
+            }
+            if let Some(font_weight) = style.font_weight {
+                properties.push(format!("fontWeight: FontWeight.w{}", font_weight));
+                description.push(format!("fontWeight: FontWeight.w{}", font_weight));
+            }
+            if let Some(letter_spacing) = style.letter_spacing {
+                properties.push(format!("letterSpacing: {}", dart_number(letter_spacing)));
+                description.push(format!("letterSpacing: {}", dart_number(letter_spacing)));
+            }
+            if let Some(height) = style.height {
+                properties.push(format!("height: {}", dart_number(height)));
+                description.push(format!("height: {}", dart_number(height)));
+            }
+
+            format!("                /// {}\n                static const {} = TextStyle({});", description.join(" "), style.name, properties.join(", "))
+        }).collect::<Vec<String>>().join("\n\n");
+
+        let colors_import = if text_color.is_some() { "\n\n            import 'app_colors.dart';" } else { "" };
+
+        return format!(
+            "
+            import 'package:flutter/material.dart';{colors_import}
+
+            /// App TextStyles
+            /// Generated from the design tokens, regenerate with: rudder tokens import <file>
+            abstract class AppTextStyles {{
+{styles}
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    // widgets added with `rudder add widget`
+    pub fn widget(class_name: &str, kind: &str) -> String {
+        return match kind {
+            "stateful" => format!(
+                "
+                import 'package:flutter/material.dart';
+
+                class {class_name} extends StatefulWidget {{
+                    const {class_name}({{super.key}});
+
+                    @override
+                    State<{class_name}> createState() => _{class_name}State();
+                }}
+
+                class _{class_name}State extends State<{class_name}> {{
+                    @override
+                    Widget build(BuildContext context) {{
+                        return const Placeholder();
+                    }}
+                }}
+                "
+            ),
+            "consumer" => format!(
+                "
+                import 'package:flutter/material.dart';
+                import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+                class {class_name} extends ConsumerWidget {{
+                    const {class_name}({{super.key}});
+
+                    @override
+                    Widget build(BuildContext context, WidgetRef ref) {{
+                        return const Placeholder();
+                    }}
+                }}
+                "
+            ),
+            _ => format!(
+                "
+                import 'package:flutter/material.dart';
+
+                class {class_name} extends StatelessWidget {{
+                    const {class_name}({{super.key}});
+
+                    @override
+                    Widget build(BuildContext context) {{
+                        return const Placeholder();
+                    }}
+                }}
+                "
+            ),
+        }.trim().to_string();
+    }
+
+    pub fn widget_test(package_name: &str, widget_import: &str, class_name: &str, kind: &str) -> String {
+        let (riverpod_import, widget) = if kind == "consumer" {
+            ("\n            import 'package:flutter_riverpod/flutter_riverpod.dart';", format!("const ProviderScope(child: MaterialApp(home: {class_name}()))"))
+        } else {
+            ("", format!("const MaterialApp(home: {class_name}())"))
+        };
+
+        return format!(
+            "
+            import 'package:flutter/material.dart';{riverpod_import}
+            import 'package:flutter_test/flutter_test.dart';
+            import 'package:{package_name}/{widget_import}';
+
+            void main() {{
+                testWidgets('{class_name} builds', (tester) async {{
+                    await tester.pumpWidget({widget});
+
+                    expect(find.byType({class_name}), findsOneWidget);
+                }});
+            }}
+            "
+        ).trim().to_string();
+    }
+
+    /// Method of the service and repository of a feature shape.
+    pub struct ShapeMethod {
+        pub signature: &'static str,
+        /// Call of the repository's method by the service.
+        pub call: &'static str,
+        pub implementation: &'static str,
+        pub fake: &'static str,
+    }
+
+    /// Data layer of a blueprint other than basic: the repository its service reads from and their methods.
+    pub struct FeatureShape {
+        pub name: &'static str,
+        /// `local` or `remote`, naming the repository.
+        pub kind: &'static str,
+        pub imports: &'static str,
+        pub fake_fields: &'static str,
+        pub methods: &'static [ShapeMethod],
+    }
+
+    pub const FEATURE_SHAPES: [FeatureShape; 4] = [
+        FeatureShape {
+            name: "list",
+            kind: "remote",
+            imports: "",
+            fake_fields: "",
+            methods: &[
+                ShapeMethod { signature: "Future<List<String>> fetchItems()", call: "fetchItems()", implementation: "async => throw UnimplementedError()", fake: "async => ['First', 'Second', 'Third']" },
+                ShapeMethod { signature: "Future<String> fetchItem(String id)", call: "fetchItem(id)", implementation: "async => throw UnimplementedError()", fake: "async => id" },
+            ],
+        },
+        FeatureShape {
+            name: "readonly",
+            kind: "remote",
+            imports: "",
+            fake_fields: "",
+            methods: &[ShapeMethod { signature: "Future<String> fetch()", call: "fetch()", implementation: "async => throw UnimplementedError()", fake: "async => 'Loaded'" }],
+        },
+        FeatureShape {
+            name: "form",
+            kind: "remote",
+            imports: "",
+            fake_fields: "",
+            methods: &[ShapeMethod { signature: "Future<void> submit(Map<String, String> values)", call: "submit(values)", implementation: "async => throw UnimplementedError()", fake: "async {}" }],
+        },
+        FeatureShape {
+            name: "settings",
+            kind: "local",
+            imports: "\n            import 'package:shared_preferences/shared_preferences.dart';",
+            fake_fields: "\n\n                final _flags = <String, bool>{};",
+            methods: &[
+                ShapeMethod {
+                    signature: "Future<bool> readFlag(String key)",
+                    call: "readFlag(key)",
+                    implementation: "async => (await SharedPreferences.getInstance()).getBool(key) ?? false",
+                    fake: "async => _flags[key] ?? false",
+                },
+                ShapeMethod {
+                    signature: "Future<void> writeFlag(String key, bool value)",
+                    call: "writeFlag(key, value)",
+                    implementation: "async => (await SharedPreferences.getInstance()).setBool(key, value)",
+                    fake: "async => _flags[key] = value",
+                },
+            ],
+        },
+    ];
+
+    pub fn feature_shape(shape: &str) -> &'static FeatureShape {
+        FEATURE_SHAPES.iter().find(|feature_shape| feature_shape.name == shape).unwrap_or(&FEATURE_SHAPES[0])
+    }
+
+    // application service of a feature shape, reading from its repository
+    pub fn shape_service(feature_name: &str, shape: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let shape = feature_shape(shape);
+        let kind = shape.kind.to_string().capitalize();
+        let signatures = shape.methods.iter().map(|method| format!("                {};", method.signature)).collect::<Vec<String>>().join("\n");
+        let implementations = shape
+            .methods
+            .iter()
+            .map(|method| format!("                @override\n                {} => ref.read({}{kind}RepositoryProvider).{};", method.signature, feature_name.to_lowercase(), method.call))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../data/{file_name}_{}_repository.dart';
+
+            abstract class Abstract{feature_name}Service {{
+{signatures}
+            }}
+
+            class {feature_name}Service implements Abstract{feature_name}Service {{
+                final Ref ref;
+
+                {feature_name}Service(this.ref);
+
+{implementations}
+            }}
+
+            final {}ServiceProvider = Provider<{feature_name}Service>((ref) => {feature_name}Service(ref));
+            ",
+            shape.kind,
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // repository of a feature shape
+    pub fn shape_repository(feature_name: &str, shape: &str) -> String {
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let shape = feature_shape(shape);
+        let kind = shape.kind.to_string().capitalize();
+        let imports = shape.imports;
+        let signatures = shape.methods.iter().map(|method| format!("                {};", method.signature)).collect::<Vec<String>>().join("\n");
+        let implementations = shape
+            .methods
+            .iter()
+            .map(|method| format!("                @override\n                {} {};", method.signature, method.implementation))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';{imports}
+
+            abstract class Abstract{feature_name}{kind}Repository {{
+{signatures}
+            }}
+
+            class {feature_name}{kind}Repository implements Abstract{feature_name}{kind}Repository {{
+                final Ref ref;
+
+                {feature_name}{kind}Repository(this.ref);
+
+{implementations}
+            }}
+
+            final {}{kind}RepositoryProvider = Provider<Abstract{feature_name}{kind}Repository>((ref) => {feature_name}{kind}Repository(ref));
+            ",
+            feature_name.to_lowercase()
+        ).trim().to_string();
+    }
+
+    // fake repository of a feature shape, returning sample data
+    pub fn shape_fake_repository(feature_name: &str, shape: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let shape = feature_shape(shape);
+        let kind = shape.kind.to_string().capitalize();
+        let fields = shape.fake_fields;
+        let implementations = shape
+            .methods
+            .iter()
+            .map(|method| format!("                @override\n                {} {};", method.signature, method.fake))
+            .collect::<Vec<String>>()
+            .join("\n\n");
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '{file_name}_{}_repository.dart';
+
+            class Fake{feature_name}{kind}Repository implements Abstract{feature_name}{kind}Repository {{
+                final Ref ref;{fields}
+
+                Fake{feature_name}{kind}Repository(this.ref);
+
+{implementations}
+            }}
+
+            final fake{feature_name}{kind}RepositoryProvider = Provider<Abstract{feature_name}{kind}Repository>((ref) => Fake{feature_name}{kind}Repository(ref));
+            ",
+            shape.kind
+        ).trim().to_string();
+    }
+
+    // controller loading the items of a list
+    pub fn list_controller(feature_name: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../application/{file_name}_service.dart';
+
+            class {feature_name}Controller extends StateNotifier<AsyncValue<List<String>>> {{
+                final Ref ref;
+
+                {feature_name}Controller(this.ref) : super(const AsyncValue.loading()) {{
+                    fetchItems();
+                }}
+
+                Future<void> fetchItems() async {{
+                    state = const AsyncValue.loading();
+                    state = await AsyncValue.guard(() => ref.read({provider}ServiceProvider).fetchItems());
+                }}
+            }}
+
+            final {provider}ControllerProvider = StateNotifierProvider.autoDispose<{feature_name}Controller, AsyncValue<List<String>>>((ref) => {feature_name}Controller(ref));
+
+            /// An item of the list, shown by the detail screen.
+            final {provider}ItemProvider = FutureProvider.autoDispose.family<String, String>((ref, id) => ref.read({provider}ServiceProvider).fetchItem(id));
+            "
+        ).trim().to_string();
+    }
+
+    // controller loading read-only data
+    pub fn readonly_controller(feature_name: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../application/{file_name}_service.dart';
+
+            class {feature_name}Controller extends StateNotifier<AsyncValue<String>> {{
+                final Ref ref;
+
+                {feature_name}Controller(this.ref) : super(const AsyncValue.loading()) {{
+                    fetch();
+                }}
+
+                Future<void> fetch() async {{
+                    state = const AsyncValue.loading();
+                    state = await AsyncValue.guard(() => ref.read({provider}ServiceProvider).fetch());
+                }}
+            }}
+
+            final {provider}ControllerProvider = StateNotifierProvider.autoDispose<{feature_name}Controller, AsyncValue<String>>((ref) => {feature_name}Controller(ref));
+            "
+        ).trim().to_string();
+    }
+
+    // controller submitting a form
+    pub fn form_controller(feature_name: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../application/{file_name}_service.dart';
+
+            class {feature_name}Controller extends StateNotifier<AsyncValue<void>> {{
+                final Ref ref;
+
+                {feature_name}Controller(this.ref) : super(const AsyncValue.data(null));
+
+                /// Returns whether the values were submitted.
+                Future<bool> submit(Map<String, String> values) async {{
+                    state = const AsyncValue.loading();
+                    state = await AsyncValue.guard(() => ref.read({provider}ServiceProvider).submit(values));
+                    return !state.hasError;
+                }}
+            }}
+
+            final {provider}ControllerProvider = StateNotifierProvider.autoDispose<{feature_name}Controller, AsyncValue<void>>((ref) => {feature_name}Controller(ref));
+            "
+        ).trim().to_string();
+    }
+
+    // controller of on/off settings
+    pub fn settings_controller(feature_name: &str) -> String {
+        let file_name = feature_name;
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+            import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+            import '../../application/{file_name}_service.dart';
+
+            class {feature_name}Controller extends StateNotifier<Map<String, bool>> {{
+                /// Keys of the settings, each shown as a switch.
+                static const keys = ['notifications'];
+
+                final Ref ref;
+
+                {feature_name}Controller(this.ref) : super(const {{}}) {{
+                    load();
+                }}
+
+                Future<void> load() async {{
+                    final service = ref.read({provider}ServiceProvider);
+                    state = {{for (final key in keys) key: await service.readFlag(key)}};
+                }}
+
+                Future<void> toggle(String key, bool value) async {{
+                    await ref.read({provider}ServiceProvider).writeFlag(key, value);
+                    state = {{...state, key: value}};
+                }}
+            }}
+
+            final {provider}ControllerProvider = StateNotifierProvider.autoDispose<{feature_name}Controller, Map<String, bool>>((ref) => {feature_name}Controller(ref));
+            "
+        ).trim().to_string();
+    }
+
+    /// Import of the l10n extension and title of a blueprint's screen, read from the ARB files when `l10n_import` is given.
+    fn screen_title(feature_name: &str, l10n_import: &str) -> (String, String) {
+        if l10n_import.is_empty() {
+            (String::new(), format!("const Text('{}')", feature_name.to_string().to_pascal_case().capitalize()))
+        } else {
+            (format!("import '{l10n_import}';\n        "), format!("Text(context.l10n.{})", l10n_title_key(feature_name)))
+        }
+    }
+
+    // screen listing items, opening their detail screen
+    pub fn list_screen(feature_name: &str, auto_route: bool, l10n_import: &str, location: &str) -> String {
+        let file_name = feature_name;
+        let (router_import, route_page) = route_page_annotation(auto_route);
+        let (l10n_import, title) = screen_title(feature_name, l10n_import);
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        let (go_router_import, open) = if auto_route {
+            ("", format!("context.router.pushNamed('{location}/${{items[index]}}')"))
+        } else {
+            ("\n        import 'package:go_router/go_router.dart';", format!("context.push('{location}/${{items[index]}}')"))
+        };
+        return format!(
+            "
+        import 'package:flutter/material.dart';{router_import}
+        import 'package:flutter_riverpod/flutter_riverpod.dart';{go_router_import}
+
+        {l10n_import}import 'controllers/{file_name}_controller.dart';
+
+        {route_page}class {feature_name}Screen extends ConsumerWidget {{
+            const {feature_name}Screen({{Key? key}}) : super(key: key);
+
+            @override
+            Widget build(BuildContext context, WidgetRef ref) {{
+                final items = ref.watch({provider}ControllerProvider);
+
+                return Scaffold(
+                    appBar: AppBar(
+                        title: {title},
+                    ),
+                    body: items.when(
+                        data: (items) => ListView.builder(
+                            itemCount: items.length,
+                            itemBuilder: (context, index) => ListTile(
+                                title: Text(items[index]),
+                                onTap: () => {open},
+                            ),
+                        ),
+                        loading: () => const Center(child: CircularProgressIndicator()),
+                        error: (error, _) => Center(child: Text('$error')),
+                    ),
+                );
+            }}
+        }}
+            "
+        ).trim().to_string();
+    }
+
+    // screen showing an item of a list
+    pub fn detail_screen(feature_name: &str, auto_route: bool, l10n_import: &str) -> String {
+        let file_name = feature_name;
+        let (router_import, route_page) = route_page_annotation(auto_route);
+        let (l10n_import, title) = screen_title(feature_name, l10n_import);
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        let path_param = if auto_route { "@PathParam('id') " } else { "" };
+        return format!(
+            "
+        import 'package:flutter/material.dart';{router_import}
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+        {l10n_import}import 'controllers/{file_name}_controller.dart';
+
+        {route_page}class {feature_name}DetailScreen extends ConsumerWidget {{
+            const {feature_name}DetailScreen({{Key? key, {path_param}required this.id}}) : super(key: key);
+
+            final String id;
+
+            @override
+            Widget build(BuildContext context, WidgetRef ref) {{
+                final item = ref.watch({provider}ItemProvider(id));
+
+                return Scaffold(
+                    appBar: AppBar(
+                        title: {title},
+                    ),
+                    body: item.when(
+                        data: (item) => Center(child: Text(item)),
+                        loading: () => const Center(child: CircularProgressIndicator()),
+                        error: (error, _) => Center(child: Text('$error')),
+                    ),
+                );
+            }}
+        }}
+            "
+        ).trim().to_string();
+    }
+
+    // screen showing read-only data
+    pub fn readonly_screen(feature_name: &str, auto_route: bool, l10n_import: &str) -> String {
+        let file_name = feature_name;
+        let (router_import, route_page) = route_page_annotation(auto_route);
+        let (l10n_import, title) = screen_title(feature_name, l10n_import);
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+        import 'package:flutter/material.dart';{router_import}
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+        {l10n_import}import 'controllers/{file_name}_controller.dart';
+
+        {route_page}class {feature_name}Screen extends ConsumerWidget {{
+            const {feature_name}Screen({{Key? key}}) : super(key: key);
+
+            @override
+            Widget build(BuildContext context, WidgetRef ref) {{
+                final data = ref.watch({provider}ControllerProvider);
+
+                return Scaffold(
+                    appBar: AppBar(
+                        title: {title},
+                        actions: [
+                            IconButton(
+                                icon: const Icon(Icons.refresh),
+                                onPressed: () => ref.read({provider}ControllerProvider.notifier).fetch(),
+                            ),
+                        ],
+                    ),
+                    body: data.when(
+                        data: (data) => Center(child: Text(data)),
+                        loading: () => const Center(child: CircularProgressIndicator()),
+                        error: (error, _) => Center(child: Text('$error')),
+                    ),
+                );
+            }}
+        }}
+            "
+        ).trim().to_string();
+    }
+
+    // screen with a form submitted through its controller
+    pub fn form_screen(feature_name: &str, auto_route: bool, l10n_import: &str) -> String {
+        let file_name = feature_name;
+        let (router_import, route_page) = route_page_annotation(auto_route);
+        let (l10n_import, title) = screen_title(feature_name, l10n_import);
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+        import 'package:flutter/material.dart';{router_import}
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+        {l10n_import}import 'controllers/{file_name}_controller.dart';
+
+        {route_page}class {feature_name}Screen extends ConsumerStatefulWidget {{
+            const {feature_name}Screen({{Key? key}}) : super(key: key);
+
+            @override
+            ConsumerState<{feature_name}Screen> createState() => _{feature_name}ScreenState();
+        }}
+
+        class _{feature_name}ScreenState extends ConsumerState<{feature_name}Screen> {{
+            final _formKey = GlobalKey<FormState>();
+            final _name = TextEditingController();
+
+            @override
+            void dispose() {{
+                _name.dispose();
+                super.dispose();
+            }}
+
+            Future<void> _submit() async {{
+                if (!_formKey.currentState!.validate()) return;
+
+                final submitted = await ref.read({provider}ControllerProvider.notifier).submit({{'name': _name.text}});
+                if (submitted && mounted) {{
+                    ScaffoldMessenger.of(context).showSnackBar(const SnackBar(content: Text('Saved')));
+                }}
+            }}
+
+            @override
+            Widget build(BuildContext context) {{
+                final submission = ref.watch({provider}ControllerProvider);
+
+                return Scaffold(
+                    appBar: AppBar(
+                        title: {title},
+                    ),
+                    body: Form(
+                        key: _formKey,
+                        child: ListView(
+                            padding: const EdgeInsets.all(16),
+                            children: [
+                                TextFormField(
+                                    controller: _name,
+                                    decoration: const InputDecoration(labelText: 'Name'),
+                                    validator: (value) => value == null || value.isEmpty ? 'Required' : null,
+                                ),
+                                const SizedBox(height: 16),
+                                ElevatedButton(
+                                    onPressed: submission.isLoading ? null : _submit,
+                                    child: const Text('Submit'),
+                                ),
+                                if (submission.hasError) Text('${{submission.error}}'),
+                            ],
+                        ),
+                    ),
+                );
+            }}
+        }}
+            "
+        ).trim().to_string();
+    }
+
+    // screen of on/off settings
+    pub fn settings_screen(feature_name: &str, auto_route: bool, l10n_import: &str) -> String {
+        let file_name = feature_name;
+        let (router_import, route_page) = route_page_annotation(auto_route);
+        let (l10n_import, title) = screen_title(feature_name, l10n_import);
+        let feature_name = feature_name.to_string().to_pascal_case().capitalize();
+        let provider = feature_name.to_lowercase();
+        return format!(
+            "
+        import 'package:flutter/material.dart';{router_import}
+        import 'package:flutter_riverpod/flutter_riverpod.dart';
+
+        {l10n_import}import 'controllers/{file_name}_controller.dart';
+
+        {route_page}class {feature_name}Screen extends ConsumerWidget {{
+            const {feature_name}Screen({{Key? key}}) : super(key: key);
+
+            @override
+            Widget build(BuildContext context, WidgetRef ref) {{
+                final settings = ref.watch({provider}ControllerProvider);
+
+                return Scaffold(
+                    appBar: AppBar(
+                        title: {title},
+                    ),
+                    body: ListView(
+                        children: [
+                            for (final key in {feature_name}Controller.keys)
+                                SwitchListTile(
+                                    title: Text(key),
+                                    value: settings[key] ?? false,
+                                    onChanged: (value) => ref.read({provider}ControllerProvider.notifier).toggle(key, value),
+                                ),
+                        ],
+                    ),
+                );
+            }}
+        }}
+            "
+        ).trim().to_string();
+    }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::parse_states;
+
+    #[test]
+    fn union_states_is_a_sealed_class() {
+        let states = parse_states("loading,loaded:List<String>,error:Object").unwrap();
+        let union = union_states("orders", &states);
+
+        assert!(union.contains("@freezed\n            sealed class OrdersState with _$OrdersState {"));
+        assert!(union.contains("const factory OrdersState.loaded(List<String> data) = _Loaded;"));
+        assert!(union.contains("const factory OrdersState.error(Object error) = _Error;"));
+    }
+}
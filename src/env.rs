@@ -0,0 +1,228 @@
+/// Comment written above a key in .env to remember its type and default.
+const METADATA_PREFIX: &str = "# rudder:";
+
+/// A key of the .env file together with the type its typed getter returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvEntry {
+    pub key: String,
+    pub value: String,
+    pub env_type: String,
+    pub default: Option<String>,
+}
+
+/// Reads the keys of a .env file. Keys without rudder metadata get a type inferred from their value.
+pub fn parse_env(content: &str) -> Vec<EnvEntry> {
+    let mut entries = Vec::new();
+    let mut metadata: Option<(Option<String>, Option<String>)> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if let Some(comment) = line.strip_prefix(METADATA_PREFIX) {
+            metadata = Some(parse_metadata(comment));
+            continue;
+        }
+
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) if !line.starts_with('#') && !key.trim().is_empty() => (key.trim(), unquote(value.trim())),
+            _ => {
+                metadata = None;
+                continue;
+            }
+        };
+
+        let (env_type, default) = metadata.take().unwrap_or((None, None));
+        let env_type = env_type.unwrap_or_else(|| infer_type(&value).to_string());
+
+        entries.push(EnvEntry { key: key.to_string(), value, env_type, default });
+    }
+
+    entries
+}
+
+fn parse_metadata(comment: &str) -> (Option<String>, Option<String>) {
+    let mut env_type = None;
+    let mut default = None;
+    let mut rest = comment.trim_start();
+
+    while let Some((name, after)) = rest.split_once('=') {
+        // quoted values may hold spaces, e.g. default="hello world"
+        let (value, remaining) = match after.starts_with('"') {
+            true => {
+                let end = quoted_end(after);
+                (serde_json::from_str::<String>(&after[..end]).unwrap_or_else(|_| after[1..end].trim_end_matches('"').to_string()), &after[end..])
+            }
+            false => {
+                let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                (after[..end].to_string(), &after[end..])
+            }
+        };
+
+        match name.trim() {
+            "type" => env_type = Some(value),
+            "default" => default = Some(value),
+            _ => (),
+        }
+
+        rest = remaining.trim_start();
+    }
+
+    (env_type, default)
+}
+
+/// Byte index after the closing quote of the JSON string `value` starts with, or its length when unclosed.
+fn quoted_end(value: &str) -> usize {
+    let mut escaped = false;
+
+    for (index, c) in value.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return index + 1,
+            _ => (),
+        }
+    }
+
+    value.len()
+}
+
+/// A default as written in the metadata comment, quoted when it isn't a single word.
+fn metadata_value(value: &str) -> String {
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        serde_json::to_string(value).expect("Unable to quote the value")
+    } else {
+        value.to_string()
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2 && ((value.starts_with('"') && value.ends_with('"')) || (value.starts_with('\'') && value.ends_with('\'')));
+    if quoted { value[1..value.len() - 1].to_string() } else { value.to_string() }
+}
+
+pub fn infer_type(value: &str) -> &'static str {
+    if value == "true" || value == "false" {
+        "bool"
+    } else if value.parse::<i64>().is_ok() {
+        "int"
+    } else if value.starts_with("http://") || value.starts_with("https://") {
+        "url"
+    } else {
+        "string"
+    }
+}
+
+/// Checks that a default value can be read as `env_type`.
+pub fn validate_value(env_type: &str, value: &str) -> Result<(), String> {
+    let valid = match env_type {
+        "int" => value.parse::<i64>().is_ok(),
+        "bool" => value == "true" || value == "false",
+        "url" => is_url(value),
+        _ => true,
+    };
+
+    if valid { Ok(()) } else { Err(format!("'{}' is not a valid {}.", value, env_type)) }
+}
+
+/// Whether `value` is an absolute url: a scheme such as `https` followed by `://` and a host with an optional port.
+fn is_url(value: &str) -> bool {
+    let (scheme, rest) = match value.split_once("://") {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.');
+
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority.rsplit_once('@').map_or(authority, |(_, host_port)| host_port);
+    // the port keeps its colon, e.g. `:8080`, and is empty when missing
+    let (host, port) = match host_port.strip_prefix('[') {
+        // IPv6 literal, e.g. http://[::1]:8080
+        Some(ipv6) => match ipv6.split_once(']') {
+            Some(parts) => parts,
+            None => return false,
+        },
+        None => host_port.split_at(host_port.find(':').unwrap_or(host_port.len())),
+    };
+
+    let valid_host = !host.is_empty() && !host.contains(|c: char| c.is_whitespace() || c.is_control());
+    let valid_port = port.is_empty() || port.strip_prefix(':').is_some_and(|port| !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()));
+
+    valid_scheme && valid_host && valid_port && !rest.contains(char::is_whitespace)
+}
+
+/// Adds `key` to the .env content, or updates the type and default of an existing key.
+pub fn upsert_env(content: &str, key: &str, env_type: &str, default: Option<&str>) -> String {
+    let metadata = match default {
+        Some(default) => format!("{} type={} default={}", METADATA_PREFIX, env_type, metadata_value(default)),
+        None => format!("{} type={}", METADATA_PREFIX, env_type),
+    };
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    match lines.iter().position(|line| line.split_once('=').is_some_and(|(name, _)| name.trim() == key)) {
+        Some(index) => {
+            if index > 0 && lines[index - 1].trim().starts_with(METADATA_PREFIX) {
+                lines[index - 1] = metadata;
+            } else {
+                lines.insert(index, metadata);
+            }
+        }
+        None => {
+            lines.push(metadata);
+            let value = default.unwrap_or_default();
+            let value = if value.contains(char::is_whitespace) { format!("\"{}\"", value) } else { value.to_string() };
+            lines.push(format!("{}={}", key, value));
+        }
+    }
+
+    format!("{}\n", lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_is_read_from_the_comment_above_a_key() {
+        let content = "# rudder: type=int default=3\nRETRIES=5\n# rudder: type=string default=\"hello \\\"world\\\"\"\nGREETING=hi\nDEBUG=true\n";
+        let entries = parse_env(content);
+
+        assert_eq!(entries[0], EnvEntry { key: "RETRIES".to_string(), value: "5".to_string(), env_type: "int".to_string(), default: Some("3".to_string()) });
+        assert_eq!(entries[1].env_type, "string");
+        assert_eq!(entries[1].default.as_deref(), Some("hello \"world\""));
+        assert_eq!(entries[2].env_type, "bool");
+        assert_eq!(entries[2].default, None);
+    }
+
+    #[test]
+    fn metadata_only_applies_to_the_next_key() {
+        let entries = parse_env("# rudder: type=int default=3\n\nPORT=8080\n");
+        assert_eq!(entries[0].default, None);
+    }
+
+    #[test]
+    fn upsert_env_appends_a_new_key() {
+        let content = upsert_env("DEBUG=true\n", "GREETING", "string", Some("hello world"));
+        assert_eq!(content, "DEBUG=true\n# rudder: type=string default=\"hello world\"\nGREETING=\"hello world\"\n");
+        assert_eq!(parse_env(&content)[1].default.as_deref(), Some("hello world"));
+    }
+
+    #[test]
+    fn upsert_env_updates_existing_metadata_and_keeps_the_value() {
+        let content = "# rudder: type=int default=3\nRETRIES=5\n";
+        assert_eq!(upsert_env(content, "RETRIES", "int", Some("4")), "# rudder: type=int default=4\nRETRIES=5\n");
+        assert_eq!(upsert_env("RETRIES=5\n", "RETRIES", "int", None), "# rudder: type=int\nRETRIES=5\n");
+    }
+
+    #[test]
+    fn urls_need_a_scheme_and_a_host() {
+        for url in ["https://api.example.com", "http://localhost:8080/v1", "http://[::1]:3000", "custom+scheme://user@host/path?q=1"] {
+            assert!(validate_value("url", url).is_ok(), "{}", url);
+        }
+        for url in ["://", "foo bar://host", "https://", "http://:8080", "1http://host", "http://host:port", "http://host name", "example.com"] {
+            assert!(validate_value("url", url).is_err(), "{}", url);
+        }
+    }
+}
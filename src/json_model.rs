@@ -0,0 +1,217 @@
+use serde_json::Value;
+
+use crate::model::{camel_case, pascal_case, unique_field_name, DartField, DartModel};
+
+/// Shape of a JSON value, merged across every sample seen for the same position.
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Only `null`s or empty arrays were seen, so nothing is known about the type.
+    Unknown,
+    Bool,
+    Int,
+    Double,
+    Str,
+    List(Box<Slot>),
+    Object(Vec<(String, Slot)>),
+    /// Samples disagree on the type.
+    Dynamic,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Slot {
+    shape: Shape,
+    nullable: bool,
+}
+
+/// Infers freezed models from a sample JSON payload. The root object becomes `root_name`;
+/// nested objects become classes named after their keys. A root array is treated as a list
+/// of samples of the root object.
+pub fn infer_models(root_name: &str, value: &Value) -> Result<Vec<DartModel>, String> {
+    let root = slot_of(value);
+
+    let root = match root.shape {
+        Shape::List(item) => *item,
+        _ => root,
+    };
+
+    match &root.shape {
+        Shape::Object(fields) => {
+            let mut models = Vec::new();
+            emit_model(&pascal_case(root_name), None, fields, &mut models);
+            Ok(models)
+        }
+        _ => Err("The JSON payload must be an object or an array of objects.".to_string()),
+    }
+}
+
+fn slot_of(value: &Value) -> Slot {
+    let shape = match value {
+        Value::Null => return Slot { shape: Shape::Unknown, nullable: true },
+        Value::Bool(_) => Shape::Bool,
+        Value::Number(number) if number.is_f64() => Shape::Double,
+        Value::Number(_) => Shape::Int,
+        Value::String(_) => Shape::Str,
+        Value::Array(items) => {
+            let item = items
+                .iter()
+                .map(slot_of)
+                .reduce(merge)
+                .unwrap_or(Slot { shape: Shape::Unknown, nullable: false });
+            Shape::List(Box::new(item))
+        }
+        Value::Object(map) => Shape::Object(map.iter().map(|(key, value)| (key.clone(), slot_of(value))).collect()),
+    };
+
+    Slot { shape, nullable: false }
+}
+
+fn merge(a: Slot, b: Slot) -> Slot {
+    let nullable = a.nullable || b.nullable;
+
+    let shape = match (a.shape, b.shape) {
+        (Shape::Unknown, shape) | (shape, Shape::Unknown) => shape,
+        (Shape::Int, Shape::Double) | (Shape::Double, Shape::Int) => Shape::Double,
+        (Shape::List(a), Shape::List(b)) => Shape::List(Box::new(merge(*a, *b))),
+        (Shape::Object(a), Shape::Object(b)) => Shape::Object(merge_objects(a, b)),
+        (a, b) if a == b => a,
+        _ => Shape::Dynamic,
+    };
+
+    Slot { shape, nullable }
+}
+
+/// Keys missing from either side become nullable.
+fn merge_objects(a: Vec<(String, Slot)>, mut b: Vec<(String, Slot)>) -> Vec<(String, Slot)> {
+    let mut merged = Vec::new();
+
+    for (key, slot) in a {
+        match b.iter().position(|(other, _)| *other == key) {
+            Some(index) => {
+                let (_, other) = b.remove(index);
+                merged.push((key, merge(slot, other)));
+            }
+            None => merged.push((key, Slot { nullable: true, ..slot })),
+        }
+    }
+
+    for (key, slot) in b {
+        merged.push((key, Slot { nullable: true, ..slot }));
+    }
+
+    merged
+}
+
+fn emit_model(name: &str, parent: Option<&str>, fields: &[(String, Slot)], models: &mut Vec<DartModel>) -> String {
+    // reserve a name before descending so nested classes can't take it
+    let index = models.len();
+    let reserved = unique_name(name, models);
+    models.push(DartModel { name: reserved.clone(), fields: Vec::new() });
+
+    let mut dart_fields: Vec<DartField> = Vec::new();
+    for (key, slot) in fields {
+        let dart_type = dart_type(slot, &pascal_case(key), &reserved, models);
+        let field_name = unique_field_name(camel_case(key), &dart_fields);
+        let json_key = if field_name != *key { Some(key.clone()) } else { None };
+        dart_fields.push(DartField { name: field_name, dart_type, json_key });
+    }
+    let fields = dart_fields;
+
+    models[index].fields = fields;
+
+    if reserved == name {
+        return reserved;
+    }
+
+    // the same object appearing under another parent reuses the existing class
+    if models[..index].iter().any(|model| model.name == name && model.fields == models[index].fields) {
+        models.truncate(index);
+        return name.to_string();
+    }
+
+    // otherwise prefer the parent's name over a numeric suffix
+    if let Some(parent) = parent {
+        let prefixed = format!("{}{}", parent, name);
+        if !models.iter().any(|model| model.name == prefixed) {
+            models[index].name = prefixed.clone();
+            return prefixed;
+        }
+    }
+
+    reserved
+}
+
+fn dart_type(slot: &Slot, class_name: &str, parent: &str, models: &mut Vec<DartModel>) -> String {
+    let dart_type = match &slot.shape {
+        Shape::Unknown | Shape::Dynamic => return "dynamic".to_string(),
+        Shape::Bool => "bool".to_string(),
+        Shape::Int => "int".to_string(),
+        Shape::Double => "double".to_string(),
+        Shape::Str => "String".to_string(),
+        Shape::List(item) => format!("List<{}>", dart_type(item, &singular(class_name), parent, models)),
+        Shape::Object(fields) => emit_model(class_name, Some(parent), fields, models),
+    };
+
+    if slot.nullable { format!("{}?", dart_type) } else { dart_type }
+}
+
+fn unique_name(name: &str, models: &[DartModel]) -> String {
+    let mut candidate = name.to_string();
+    let mut suffix = 2;
+
+    while models.iter().any(|model| model.name == candidate) {
+        candidate = format!("{}{}", name, suffix);
+        suffix += 1;
+    }
+
+    candidate
+}
+
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies") {
+        format!("{}y", stem)
+    } else if name.ends_with('s') && !name.ends_with("ss") {
+        name[..name.len() - 1].to_string()
+    } else {
+        format!("{}Item", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn field<'a>(models: &'a [DartModel], model: &str, name: &str) -> &'a DartField {
+        let model = models.iter().find(|candidate| candidate.name == model).expect("model");
+        model.fields.iter().find(|candidate| candidate.name == name).expect("field")
+    }
+
+    #[test]
+    fn missing_key_becomes_nullable() {
+        let models = infer_models("user", &json!([{"id": 1, "name": "a"}, {"id": 2}])).unwrap();
+        assert_eq!(field(&models, "User", "id").dart_type, "int");
+        assert_eq!(field(&models, "User", "name").dart_type, "String?");
+    }
+
+    #[test]
+    fn int_and_double_become_double() {
+        let models = infer_models("product", &json!([{"price": 1}, {"price": 1.5}])).unwrap();
+        assert_eq!(field(&models, "Product", "price").dart_type, "double");
+    }
+
+    #[test]
+    fn identical_nested_objects_are_deduplicated() {
+        let sample = json!({"home": {"address": {"city": "x"}}, "work": {"address": {"city": "y"}}});
+        let models = infer_models("person", &sample).unwrap();
+        let addresses = models.iter().filter(|model| model.name.ends_with("Address")).count();
+        assert_eq!(addresses, 1);
+    }
+
+    #[test]
+    fn keyword_and_digit_keys_get_a_json_key() {
+        let models = infer_models("settings", &json!({"return": 1, "2fa": true, "name": "a"})).unwrap();
+        assert_eq!(field(&models, "Settings", "returnValue").json_key.as_deref(), Some("return"));
+        assert_eq!(field(&models, "Settings", "field2fa").json_key.as_deref(), Some("2fa"));
+        assert_eq!(field(&models, "Settings", "name").json_key, None);
+    }
+}
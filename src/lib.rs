@@ -1,83 +1,2034 @@
 mod args;
+pub mod blueprints;
+pub mod config;
+mod wizard;
 mod dart;
+mod env;
+mod json_model;
+mod model;
+pub mod manifest;
+mod openapi;
+mod presets;
+pub mod report;
+mod templates;
+mod theme;
+mod tokens;
 
 use std::fs::create_dir;
 use std::fs::File;
 use std::path::Path;
-use std::io::Write;
+use std::io::{IsTerminal, Write};
 use dart::*;
+use model::{camel_case, dart_default_value, method_arguments, method_signature, parse_fields, parse_states, pascal_case, uses_custom_types, DartModel};
 use std::process::Command;
+use config::ProjectConfig;
+use manifest::{FileState, Manifest};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde_json::json;
+use report::{Code, FileStatus};
 
-pub fn add_feature(feature_name: &str, sub_feature_name: Option<&str>) {
+/// Adds a feature with the files, routes and dependencies of a blueprint. Returns false when the feature wasn't added.
+pub fn add_feature(feature_name: &str, sub_feature_name: Option<&str>, states: Option<&str>, with_tests: bool, blueprint: &blueprints::Blueprint) -> bool {
+    let config = match ProjectConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            report::error(Code::InvalidInput, &e);
+            return false;
+        }
+    };
+
+    // parse the union states before anything is written, features get the project's states by default
+    let states_source = states.or(config.default_states());
+    let states = match states_source.map(parse_states).transpose() {
+        Ok(states) => states,
+        Err(e) => {
+            report::error(Code::InvalidArgument, &e);
+            return false;
+        }
+    };
+
+    let base_path = feature_path(feature_name, sub_feature_name);
+
+    if !check_conflicts(&feature_file_paths(blueprint, &base_path, sub_feature_name.unwrap_or(feature_name), with_tests)) {
+        return false;
+    }
+
+    // create [feature_name] folder
+    create_folder(&base_path);
+
+     // Create sub_features folder if doesnt exist
+    if !Path::new(format!("lib\\src\\features\\{}\\sub_features", feature_name).as_str()).exists() {
+        create_folder(format!("lib\\src\\features\\{}\\sub_features", feature_name).as_str());
+    }
+
+    let sub_feature_path = sub_feature_name.map(|_| base_path.clone());
+    let feature_name: &str = match sub_feature_name {
+        Some(sub_feature_name) => sub_feature_name,
+        None => feature_name,
+    }; 
+
+    let localized = l10n_enabled();
+    let kinds = blueprint.fake_kinds();
+    let initial_state = states.as_ref().and_then(|states| states.iter().find(|state| state.payload.is_none())).map(|state| state.name.clone());
+
+    // values every template of the blueprint is rendered with
+    let mut variables = json!({
+        "feature": feature_name,
+        "auto_route": !config.uses_go_router(),
+        "exceptions_import": exceptions_import(sub_feature_name.is_some()),
+        "l10n_import": if localized { l10n_import(sub_feature_name.is_some()) } else { String::new() },
+        "localized": localized,
+        "location": feature_route(feature_name, sub_feature_path.as_deref()).0,
+        "kinds": kinds,
+        "initial_state": initial_state,
+    });
+    if let Some(states_source) = states_source {
+        variables["states"] = json!(states_source);
+    }
+
+    if localized && blueprint.files.iter().any(|file| file.path.ends_with("_screen.dart")) {
+        add_l10n_string(&l10n_title_key(feature_name), &feature_name.to_string().to_pascal_case().capitalize());
+    }
+
+    for file in &blueprint.files {
+        generate_blueprint_file(blueprint, &base_path, file, states.is_some(), &variables);
+    }
+
+    if !kinds.is_empty() {
+        register_fake_repositories(&base_path, feature_name, &kinds);
+    }
+
+    for route in &blueprint.routes {
+        let screen = route.screen.replace("{feature}", feature_name);
+        if config.uses_go_router() {
+            register_go_route(feature_name, sub_feature_path.as_deref(), &screen, &route.path);
+        } else {
+            register_auto_route(feature_name, sub_feature_path.as_deref(), &screen, &route.path);
+        }
+    }
+
+    if with_tests && !blueprint.tests.is_empty() {
+        add_feature_tests(blueprint, &base_path, states.is_some(), variables);
+    }
+
+    if states.is_some() {
+        ensure_dependencies(&[FREEZED_ANNOTATION], &["build_runner", FREEZED]);
+    }
+
+    if !blueprint.dependencies.is_empty() || !blueprint.dev_dependencies.is_empty() {
+        ensure_dependencies(
+            &blueprint.dependencies.iter().map(String::as_str).collect::<Vec<&str>>(),
+            &blueprint.dev_dependencies.iter().map(String::as_str).collect::<Vec<&str>>(),
+        );
+    }
+
+    true
+}
+
+/// Template of a blueprint's file for this feature: the screen reads its strings from the ARB files once
+/// l10n is set up, and the controller exposes the union states when the feature has some.
+fn feature_template(template: &str, has_states: bool, localized: bool) -> &str {
+    match template {
+        "screen" if localized => "localized_screen",
+        "controller" if has_states => "state_controller",
+        "union_states" if !has_states => "empty",
+        _ => template,
+    }
+}
+
+/// Path of a blueprint's file under `root`, e.g. `presentation/{feature}_screen.dart` -> `{root}\\presentation\\orders_screen.dart`.
+fn blueprint_file_path(root: &str, file: &blueprints::BlueprintFile, feature_name: &str) -> String {
+    format!("{}\\{}", root, file.path.replace("{feature}", feature_name).replace('/', "\\"))
+}
+
+/// Files a blueprint generates for a feature, its tests included.
+fn feature_file_paths(blueprint: &blueprints::Blueprint, base_path: &str, feature_name: &str, with_tests: bool) -> Vec<String> {
+    let mut paths: Vec<String> = blueprint.files.iter().map(|file| blueprint_file_path(base_path, file, feature_name)).collect();
+
+    if with_tests {
+        let test_path = format!("test\\{}", base_path.trim_start_matches("lib\\"));
+        paths.extend(blueprint.tests.iter().map(|file| blueprint_file_path(&test_path, file, feature_name)));
+    }
+
+    paths
+}
+
+/// Generates a file of a blueprint under `root`, from a template of the blueprint's folder or of the registry.
+fn generate_blueprint_file(blueprint: &blueprints::Blueprint, root: &str, file: &blueprints::BlueprintFile, has_states: bool, variables: &serde_json::Value) {
+    let feature_name = variables["feature"].as_str().unwrap_or_default();
+    let path = blueprint_file_path(root, file, feature_name);
+    let (folder, name) = path.rsplit_once('\\').unwrap_or(("", &path));
+    create_folder_all(folder);
+
+    let mut variables = variables.clone();
+    for (key, value) in &file.variables {
+        variables[key] = value.clone();
+    }
+
+    match blueprint.template_path(&file.template) {
+        Some(source) => {
+            variables["source"] = json!(source);
+            variables["class_name"] = json!(feature_name.to_string().to_pascal_case().capitalize());
+            if variables.get("package_name").is_none() {
+                variables["package_name"] = json!(package_name().unwrap_or_default());
+            }
+            generate_file(folder, name, templates::FILE_TEMPLATE, variables);
+        }
+        None => {
+            let template = feature_template(&file.template, has_states, variables["localized"].as_bool().unwrap_or_default());
+            if templates::find(template).and_then(|template| template.render).is_none() {
+                report::warning(Code::InvalidInput, &format!("Unknown template {} in the {} blueprint. Skipping {}.", template, blueprint.name, path));
+                return;
+            }
+            generate_file(folder, name, template, variables);
+        }
+    }
+}
+
+/// Adds a feature's fake repositories to the overrides applied when running with USE_FAKES.
+fn register_fake_repositories(base_path: &str, feature_name: &str, kinds: &[String]) {
+    let fake_overrides = "lib\\src\\fake_overrides.dart";
+
+    if !Path::new(fake_overrides).exists() {
+        generate_file("lib\\src", "fake_overrides.dart", "fake_overrides", json!({}));
+    }
+
+    let (entry_path, entry_import) = entry_point();
+
+    if !std::fs::read_to_string(&entry_path).unwrap_or_default().contains("fakeOverrides") {
+        let wired = add_import(&entry_path, &format!("import '{}fake_overrides.dart';", entry_import))
+            && insert_after_line(&entry_path, "overrides: [", "if (useFakes) ...fakeOverrides,");
+        if !wired {
+            report::warning(Code::ManualStep, &format!("Unable to add the fake overrides to {}. Add `if (useFakes) ...fakeOverrides` to its overrides manually.", entry_path));
+        }
+    }
+
+    // lib\src\features\orders -> features/orders
+    let data_import = format!("{}/data", base_path.trim_start_matches("lib\\src\\").replace('\\', "/"));
+    let pascal_name = feature_name.to_string().to_pascal_case();
+
+    for kind in kinds {
+        let provider = format!("{}{}RepositoryProvider", pascal_name.to_lowercase(), kind.to_string().capitalize());
+        let fake = format!("Fake{}{}Repository", pascal_name, kind.to_string().capitalize());
+
+        add_import(fake_overrides, &format!("import '{}/{}_{}_repository.dart';", data_import, feature_name, kind));
+        add_import(fake_overrides, &format!("import '{}/fake_{}_{}_repository.dart';", data_import, feature_name, kind));
+        // features added again keep their single override
+        let override_line = format!("{}.overrideWith((ref) => {}(ref)),", provider, fake);
+        if !std::fs::read_to_string(fake_overrides).unwrap_or_default().contains(&override_line) {
+            insert_after_line(fake_overrides, "final fakeOverrides = <Override>[", &override_line);
+        }
+    }
+}
+
+/// Mirrors a feature under test/ with the tests of its blueprint, e.g. unit tests for its service and controller and a widget test for its screen.
+fn add_feature_tests(blueprint: &blueprints::Blueprint, base_path: &str, has_states: bool, mut variables: serde_json::Value) {
+    let package_name = match package_name() {
+        Some(package_name) => package_name,
+        None => {
+            report::warning(Code::NotFound, "Unable to read the package name from pubspec.yaml. Skipping tests.");
+            return;
+        }
+    };
+
+    // lib\src\features\orders -> src/features/orders
+    variables["package_name"] = json!(package_name);
+    variables["feature_import"] = json!(base_path.trim_start_matches("lib\\").replace('\\', "/"));
+    let test_path = format!("test\\{}", base_path.trim_start_matches("lib\\"));
+
+    for file in &blueprint.tests {
+        generate_blueprint_file(blueprint, &test_path, file, has_states, &variables);
+    }
+}
+
+/// Name of the Dart package, read from pubspec.yaml.
+pub fn package_name() -> Option<String> {
+    std::fs::read_to_string("pubspec.yaml")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("name:"))
+        .map(|name| name.trim().to_string())
+}
+
+pub fn add_model(feature_name: &str, sub_feature_name: Option<&str>, model_name: &str, fields: &str) {
+    let fields = match parse_fields(fields) {
+        Ok(fields) => fields,
+        Err(e) => {
+            report::error(Code::InvalidArgument, &e);
+            return;
+        }
+    };
+
+    let model = DartModel { name: pascal_case(model_name), fields };
+
+    write_models(feature_name, sub_feature_name, &[model]);
+
+    ensure_dependencies(&[FREEZED_ANNOTATION, "json_annotation"], &["build_runner", FREEZED, "json_serializable"]);
+}
+
+pub fn add_model_from_json(feature_name: &str, sub_feature_name: Option<&str>, model_name: Option<&str>, json_path: &str) {
+    let json = match std::fs::read_to_string(json_path) {
+        Ok(json) => json,
+        Err(e) => {
+            report::error(Code::NotFound, &format!("Unable to read {}: {}", json_path, e));
+            return;
+        }
+    };
+
+    let value: serde_json::Value = match serde_json::from_str(&json) {
+        Ok(value) => value,
+        Err(e) => {
+            report::error(Code::InvalidInput, &format!("{} is not valid JSON: {}", json_path, e));
+            return;
+        }
+    };
+
+    let model_name = model_name.unwrap_or(sub_feature_name.unwrap_or(feature_name));
+
+    let models = match json_model::infer_models(model_name, &value) {
+        Ok(models) => models,
+        Err(e) => {
+            report::error(Code::InvalidInput, &e);
+            return;
+        }
+    };
+
+    write_models(feature_name, sub_feature_name, &models);
+
+    ensure_dependencies(&[FREEZED_ANNOTATION, "json_annotation"], &["build_runner", FREEZED, "json_serializable"]);
+}
+
+/// Appends freezed models to a feature's models file, adding the freezed header if missing.
+pub fn write_models(feature_name: &str, sub_feature_name: Option<&str>, models: &[DartModel]) {
+    let name = sub_feature_name.unwrap_or(feature_name);
+    let path = format!("{}\\domain\\{}_models.dart", feature_path(feature_name, sub_feature_name), name);
+
+    if !Path::new(&path).exists() {
+        report::error(Code::NotFound, &format!("{} does not exist. Add the feature first with: rudder add --features {}", path, feature_name));
+        return;
+    }
+
+    let mut content = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) => {
+            report::error(Code::Io, &format!("Unable to read {}: {}", path, e));
+            return;
+        }
+    };
+
+    if !content.contains("freezed_annotation") {
+        content = format!("{}\n\n{}", models_header(name), content.trim_start());
+    }
+
+    for model in models {
+        if content.contains(&format!("class {} with", model.name)) {
+            report::warning(Code::AlreadyExists, &format!("{} already exists in {}. Skipping.", model.name, path));
+            continue;
+        }
+
+        content = format!("{}\n\n{}\n", content.trim_end(), freezed_model(model));
+        report::info(&format!("Adding {} model to {}", model.name, path));
+    }
+
+    write_file(&path, content);
+}
+
+pub fn import_openapi(spec_path: &str, feature_name: &str, tag: Option<&str>) {
+    let source = match std::fs::read_to_string(spec_path) {
+        Ok(source) => source,
+        Err(e) => {
+            report::error(Code::NotFound, &format!("Unable to read {}: {}", spec_path, e));
+            return;
+        }
+    };
+
+    let spec = match openapi::parse_spec(&source).and_then(|spec| openapi::read_spec(&spec, tag)) {
+        Ok(spec) => spec,
+        Err(e) => {
+            report::error(Code::InvalidInput, &format!("Unable to import {}: {}", spec_path, e));
+            return;
+        }
+    };
+
+    if spec.operations.is_empty() {
+        report::error(Code::InvalidInput, &format!("No operations found in {}.", spec_path));
+        return;
+    }
+
+    let base_path = feature_path(feature_name, None);
+    let data_path = format!("{}\\data", base_path);
+
+    let repositories = [format!("{}_remote_repository.dart", feature_name), format!("fake_{}_remote_repository.dart", feature_name)];
+    if !check_conflicts(&repositories.iter().map(|name| format!("{}\\{}", data_path, name)).collect::<Vec<String>>()) {
+        return;
+    }
+
+    if !Path::new(&base_path).exists() {
+        let added = match blueprints::find(blueprints::DEFAULT_BLUEPRINT) {
+            Ok(blueprint) => add_feature(feature_name, None, None, true, &blueprint),
+            Err(e) => {
+                report::error(Code::InvalidInput, &e);
+                false
+            }
+        };
+        if !added {
+            return;
+        }
+    }
+
+    if !spec.models.is_empty() {
+        write_models(feature_name, None, &spec.models);
+    }
+
+    let signatures: Vec<String> = spec.operations.iter().map(|operation| operation.signature()).collect();
+
+    let implementations: Vec<String> = spec.operations.iter().map(|operation| format!(
+        "                @override\n                {} async {{\n                    {}\n                }}",
+        operation.signature(),
+        operation.request_body(&spec.models).replace('\n', "\n                    ")
+    )).collect();
+
+    for operation in spec.operations.iter().filter(|operation| !operation.has_fake(&spec.models)) {
+        report::warning(Code::ManualStep, &format!("Unable to build a fake {} for {}, fill in the TODO in the fake repository.", operation.returns, operation.name));
+    }
+
+    let fakes: Vec<String> = spec.operations.iter().map(|operation| format!(
+        "                @override\n                {} async {}",
+        operation.signature(),
+        operation.fake_body(&spec.models)
+    )).collect();
+
+    let variables = json!({ "spec": spec_path, "feature": feature_name, "tag": tag });
+    create_generated_file(&data_path, &repositories[0], "api_remote_repository", variables.clone(), api_remote_repository(feature_name, &signatures, &implementations));
+    create_generated_file(&data_path, &repositories[1], "api_fake_remote_repository", variables, api_fake_remote_repository(feature_name, &fakes));
+
+    ensure_http_client_service(spec.base_url.as_deref());
+
+    ensure_dependencies(&["dio", FREEZED_ANNOTATION, "json_annotation"], &["build_runner", FREEZED, "json_serializable"]);
+
+    report::info(&format!("Imported {} operations and {} models into {}.", spec.operations.len(), spec.models.len(), feature_name));
+}
+
+pub fn add_env(key: &str, env_type: Option<&str>, default: Option<&str>) {
+    let content = std::fs::read_to_string(".env").unwrap_or_default();
+
+    let existing = env::parse_env(&content).into_iter().find(|entry| entry.key == key);
+    let env_type = env_type
+        .map(str::to_string)
+        .or(existing.map(|entry| entry.env_type))
+        .unwrap_or_else(|| default.map_or("string", env::infer_type).to_string());
+
+    if let Some(default) = default {
+        if let Err(e) = env::validate_value(&env_type, default) {
+            report::error(Code::InvalidArgument, &format!("Invalid default for {}: {}", key, e));
+            return;
+        }
+    }
+
+    if !check_conflicts(&[ENV_DART_PATH.to_string()]) {
+        return;
+    }
+
+    write_file(".env", env::upsert_env(&content, key, &env_type, default));
+    report::info(&format!("Adding {} ({}) to .env", key, env_type));
+
+    // keep the env files of the flavors in step
+    for flavor_env in flavor_env_files() {
+        let flavor_content = std::fs::read_to_string(&flavor_env).unwrap_or_default();
+        write_file(&flavor_env, env::upsert_env(&flavor_content, key, &env_type, default));
+        report::info(&format!("Adding {} ({}) to {}", key, env_type, flavor_env));
+    }
+
+    sync_env();
+}
+
+const ENV_DART_PATH: &str = "lib\\src\\constants\\env.dart";
+
+/// Regenerates the typed Env class from the keys of .env.
+pub fn sync_env() {
+    let content = match std::fs::read_to_string(".env") {
+        Ok(content) => content,
+        Err(_) => {
+            report::error(Code::NotFound, ".env does not exist. Add a key with: rudder env add KEY");
+            return;
+        }
+    };
+
+    if !check_conflicts(&[ENV_DART_PATH.to_string()]) {
+        return;
+    }
+
+    let entries = env::parse_env(&content);
+    let constants = "lib\\src\\constants";
+
+    create_folder_all(constants);
+
+    // regenerated on every sync, so any existing file is replaced
+    create_generated_file(constants, "env.dart", "env", json!({}), env_dart(&entries));
+
+    let (entry_path, entry_import) = entry_point();
+    let wired = add_import(&entry_path, &format!("import '{}constants/env.dart';", entry_import))
+        && insert_after_line(&entry_path, "await dotenv.load(", "Env.validate();");
+
+    if !wired {
+        report::warning(Code::ManualStep, &format!("Unable to call Env.validate() in {}. Call it after dotenv.load manually.", entry_path));
+    }
+}
+
+pub fn add_flavors(flavors: &str) {
+    let flavors: Vec<String> = flavors.split(',').map(|flavor| camel_case(flavor.trim())).filter(|flavor| !flavor.is_empty()).collect();
+
+    if flavors.is_empty() {
+        report::error(Code::InvalidArgument, "No flavors given. Example: rudder flavors add dev,staging,prod");
+        return;
+    }
+
+    let bootstrap_path = "lib\\src\\bootstrap.dart";
+
+    // main.dart is replaced by the entry point of the first flavor, and backed up unless a policy was given
+    let explicit_policy = *CONFLICT_POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut targets = vec!["lib\\src\\constants\\flavor.dart".to_string()];
+    if !Path::new(bootstrap_path).exists() && explicit_policy.is_some() {
+        targets.push("lib\\main.dart".to_string());
+    }
+    if !check_conflicts(&targets) {
+        return;
+    }
+
+    // the body of main.dart becomes the bootstrap shared by every flavor
+    if !Path::new(bootstrap_path).exists() {
+        let main = match std::fs::read_to_string("lib\\main.dart") {
+            Ok(main) if main.contains("void main() async {") => main,
+            _ => {
+                report::error(Code::InvalidInput, "lib\\main.dart does not contain `void main() async {`. Unable to extract the bootstrap.");
+                return;
+            }
+        };
+
+        create_generated_file("lib\\src", "bootstrap.dart", "bootstrap", json!({}), bootstrap_from_main(&main));
+        add_import(bootstrap_path, "import 'constants/flavor.dart';");
+        insert_after_line(bootstrap_path, "overrides: [", "flavorProvider.overrideWithValue(flavor),");
+        // its body now lives in the bootstrap
+        set_conflict_policy(explicit_policy.unwrap_or(ConflictPolicy::Backup));
+        generate_file("lib", "main.dart", "flavor_main", json!({ "flavor": flavors[0] }));
+        *CONFLICT_POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = explicit_policy;
+    }
+
+    // keep the flavors added before
+    let mut all_flavors = existing_flavors();
+    for flavor in &flavors {
+        if !all_flavors.contains(flavor) {
+            all_flavors.push(flavor.clone());
+        }
+    }
+
+    create_folder_all("lib\\src\\constants");
+    generate_file("lib\\src\\constants", "flavor.dart", "flavor", json!({ "flavors": all_flavors }));
+
+    let env = std::fs::read_to_string(".env").unwrap_or_default();
+    let mut assets = Vec::new();
+
+    for flavor in &flavors {
+        let file_name = format!("main_{}.dart", words_to_snake_case(flavor));
+        if !Path::new(&format!("lib\\{}", file_name)).exists() {
+            generate_file("lib", &file_name, "flavor_main", json!({ "flavor": flavor }));
+        } else {
+            report::file(&format!("lib\\{}", file_name), FileStatus::Skipped);
+        }
+
+        let env_file = format!(".env.{}", flavor);
+        if !Path::new(&env_file).exists() {
+            create_generated_file("", &env_file, "flavor_env", json!({ "flavor": flavor }), env.clone());
+        } else {
+            report::file(&env_file, FileStatus::Skipped);
+        }
+        assets.push(env_file);
+    }
+
+    add_pubspec_assets(&assets);
+
+    report::info(&format!("Flavors: {}. Run one with: flutter run -t lib/main_{}.dart", all_flavors.join(", "), words_to_snake_case(&flavors[0])));
+}
+
+pub fn init_l10n(locales: &str) {
+    let locales: Vec<String> = locales.split(',').map(|locale| locale.trim().replace('-', "_")).filter(|locale| !locale.is_empty()).collect();
+
+    if locales.is_empty() || locales.iter().any(|locale| !locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')) {
+        report::error(Code::InvalidArgument, "Invalid locales. Example: rudder l10n init --locales en,es");
+        return;
+    }
+
+    if !Path::new("l10n.yaml").exists() {
+        generate_file("", "l10n.yaml", "l10n_yaml", json!({ "template_locale": locales[0] }));
+    } else {
+        report::file("l10n.yaml", FileStatus::Skipped);
+    }
+
+    create_folder_all("lib\\src\\l10n");
+
+    // new locales start with the strings of the template, to be translated
+    let template = read_arb(&template_arb_path());
+    let app_title = ProjectConfig::load().ok().and_then(|config| config.app_title).unwrap_or_else(|| app_title_from_package(&package_name().unwrap_or_default()));
+
+    for locale in &locales {
+        let arb_path = format!("lib\\src\\l10n\\app_{}.arb", locale);
+        if Path::new(&arb_path).exists() {
+            report::file(&arb_path, FileStatus::Skipped);
+            continue;
+        }
+
+        let mut arb = serde_json::Map::new();
+        arb.insert("@@locale".to_string(), serde_json::Value::String(locale.clone()));
+        arb.insert("appTitle".to_string(), serde_json::Value::String(app_title.clone()));
+        for (key, value) in &template {
+            if !key.starts_with('@') {
+                arb.insert(key.clone(), value.clone());
+            }
+        }
+
+        write_arb(&arb_path, &arb);
+    }
+
+    if !Path::new("lib\\src\\l10n\\l10n.dart").exists() {
+        generate_file("lib\\src\\l10n", "l10n.dart", "l10n", json!({}));
+    }
+
+    wire_l10n_app();
+    localize_generated_screens();
+
+    if !std::fs::read_to_string("pubspec.yaml").unwrap_or_default().contains("flutter_localizations:") {
+        // the project opted out of installing packages in rudder create
+        if ProjectConfig::load().map_or(true, |config| config.install_dependencies) {
+            pub_add(&["flutter_localizations", "--sdk=flutter"], false);
+        } else {
+            report::info("Not installing flutter_localizations. Add it with flutter pub add flutter_localizations --sdk=flutter.");
+        }
+    }
+    ensure_dependencies(&["intl"], &[]);
+    set_pubspec_flutter_option("generate", "true");
+
+    report::info("Run `flutter gen-l10n` (or `flutter run`) to generate AppLocalizations.");
+}
+
+/// Whether `rudder l10n init` was run, so generated screens read their strings from the ARB files.
+fn l10n_enabled() -> bool {
+    Path::new("lib\\src\\l10n\\l10n.dart").exists()
+}
+
+/// ARB file the other locales are generated from, as set in l10n.yaml.
+fn template_arb_path() -> String {
+    let template = std::fs::read_to_string("l10n.yaml")
+        .unwrap_or_default()
+        .lines()
+        .find_map(|line| line.strip_prefix("template-arb-file:"))
+        .map_or("app_en.arb".to_string(), |file| file.trim().to_string());
+
+    format!("lib\\src\\l10n\\{}", template)
+}
+
+fn read_arb(path: &str) -> serde_json::Map<String, serde_json::Value> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_arb(path: &str, arb: &serde_json::Map<String, serde_json::Value>) {
+    let content = serde_json::to_string_pretty(arb).expect("Error serializing ARB file.");
+    write_file(path, format!("{}\n", content));
+}
+
+/// Adds a string to every ARB file, keeping the value of locales that already define it.
+pub fn add_l10n_string(key: &str, value: &str) {
+    let template_path = template_arb_path();
+    let mut arb_paths = vec![template_path.clone()];
+
+    if let Ok(entries) = std::fs::read_dir("lib\\src\\l10n") {
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let arb_path = format!("lib\\src\\l10n\\{}", file_name);
+            if file_name.ends_with(".arb") && !arb_paths.contains(&arb_path) {
+                arb_paths.push(arb_path);
+            }
+        }
+    }
+
+    for arb_path in arb_paths {
+        let mut arb = read_arb(&arb_path);
+        if arb.contains_key(key) {
+            continue;
+        }
+
+        arb.insert(key.to_string(), serde_json::Value::String(value.to_string()));
+        report::info(&format!("Adding {} to {}", key, arb_path));
+        write_arb(&arb_path, &arb);
+    }
+}
+
+/// Reads the localizations delegates, supported locales and title of the MaterialApp from AppLocalizations.
+fn wire_l10n_app() {
+    let app_path = "lib\\src\\app.dart";
+    let content = std::fs::read_to_string(app_path).unwrap_or_default();
+
+    if content.contains("AppLocalizations.localizationsDelegates") {
+        return;
+    }
+
+    // the title of the MaterialApp now comes from the ARB files, other titles of app.dart are left alone
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let start = lines.iter().position(|line| line.contains("MaterialApp(") || line.contains("MaterialApp.router("));
+
+    let title = start.and_then(|start| {
+        let mut depth = 0;
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            // only the arguments of the MaterialApp itself
+            if index > start && depth == 1 && line.trim_start().starts_with("title: '") {
+                return Some(index);
+            }
+            for c in line.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => (),
+                }
+            }
+            if depth == 0 && index > start {
+                return None;
+            }
+        }
+        None
+    });
+
+    match title {
+        Some(index) => {
+            lines[index] = format!("{}onGenerateTitle: (context) => context.l10n.appTitle,", leading_whitespace(&lines[index]));
+            write_lines(app_path, &lines.iter().map(String::as_str).collect::<Vec<&str>>(), content.ends_with('\n'));
+        }
+        None => report::warning(Code::ManualStep, &format!("Unable to find the title of the MaterialApp in {}. Set onGenerateTitle: (context) => context.l10n.appTitle manually.", app_path)),
+    }
+
+    let wired = add_import(app_path, "import 'l10n/l10n.dart';")
+        && insert_after_line(app_path, "debugShowCheckedModeBanner:", "supportedLocales: AppLocalizations.supportedLocales,")
+        && insert_after_line(app_path, "debugShowCheckedModeBanner:", "localizationsDelegates: AppLocalizations.localizationsDelegates,");
+
+    if !wired {
+        report::warning(Code::ManualStep, &format!("Unable to wire the localizations into {}. Set localizationsDelegates and supportedLocales of the MaterialApp manually.", app_path));
+    }
+}
+
+/// Switches the screens (and their tests) still matching the generated template over to the ARB files.
+fn localize_generated_screens() {
+    let package_name = package_name().unwrap_or_default();
+    let config = ProjectConfig::load().unwrap_or_default();
+
+    for (feature_name, sub_feature_name) in feature_names() {
+        let base_path = feature_path(&feature_name, sub_feature_name.as_deref());
+        let name = sub_feature_name.as_deref().unwrap_or(&feature_name);
+
+        let screen_path = format!("{}\\presentation\\{}_screen.dart", base_path, name);
+        if std::fs::read_to_string(&screen_path).unwrap_or_default().trim() != stateless_widget(name, !config.uses_go_router()) {
+            continue;
+        }
+
+        add_l10n_string(&l10n_title_key(name), &name.to_string().to_pascal_case().capitalize());
+        report::info(&format!("Localizing {}", screen_path));
+        let variables = json!({ "feature": name, "l10n_import": l10n_import(sub_feature_name.is_some()), "auto_route": !config.uses_go_router() });
+        rewrite_generated_file(&screen_path, "localized_screen", variables);
+
+        let feature_import = base_path.trim_start_matches("lib\\").replace('\\', "/");
+        let test_path = format!("test\\{}\\presentation\\{}_screen_test.dart", base_path.trim_start_matches("lib\\"), name);
+        if std::fs::read_to_string(&test_path).unwrap_or_default().trim() == screen_test(&package_name, &feature_import, name, false) {
+            rewrite_generated_file(&test_path, "screen_test", json!({ "package_name": package_name, "feature_import": feature_import, "feature": name, "localized": true }));
+        }
+    }
+}
+
+/// Re-renders every generated file with the current templates. Edits made since the file was
+/// generated are kept with a three-way merge between the generated, current and re-rendered content.
+pub fn upgrade() {
+    let manifest = match Manifest::load() {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            report::error(Code::InvalidInput, &e);
+            return;
+        }
+    };
+
+    if manifest.files.is_empty() {
+        report::error(Code::NotFound, &format!("{} doesn't list any generated file. Nothing to upgrade.", manifest::MANIFEST_PATH));
+        return;
+    }
+
+    let mut replaced = 0;
+    let mut merged = 0;
+    let mut up_to_date = 0;
+    let mut skipped = 0;
+    let mut conflicts: Vec<&str> = Vec::new();
+
+    for (path, entry) in &manifest.files {
+        let rendered = match templates::render(&entry.template, &entry.variables) {
+            Some(rendered) => rendered,
+            None => {
+                report::detail(&format!("Skipping {}, it is regenerated by the command that created it", path));
+                skipped += 1;
+                continue;
+            }
+        };
+
+        let current = match std::fs::read_to_string(path) {
+            Ok(current) => current,
+            Err(_) => {
+                report::detail(&format!("Skipping {}, it was deleted", path));
+                skipped += 1;
+                continue;
+            }
+        };
+
+        // an untouched file is its own base
+        let base = match manifest::read_base(path).or_else(|| (manifest::hash(&current) == entry.hash).then(|| current.clone())) {
+            Some(base) => base,
+            None => {
+                report::warning(Code::NotFound, &format!("{} was edited and its generated content wasn't kept. Skipping.", path));
+                skipped += 1;
+                continue;
+            }
+        };
+
+        if rendered == base {
+            up_to_date += 1;
+            continue;
+        }
+
+        let content = if current == base {
+            replaced += 1;
+            rendered.clone()
+        } else {
+            match three_way_merge(&base, &current, &rendered) {
+                Ok(content) => {
+                    merged += 1;
+                    content
+                }
+                Err(content) => {
+                    report::detail(&format!("Conflicts in {}", path));
+                    conflicts.push(path);
+                    content
+                }
+            }
+        };
+
+        if content != current {
+            write_file(path, &content);
+        }
+        record_generated_file(path, &entry.template, serde_json::Value::Object(entry.variables.clone()), &rendered);
+    }
+
+    if replaced + merged + conflicts.len() == 0 {
+        report::info(&format!("All generated files are up to date ({} skipped).", skipped));
+        return;
+    }
+
+    report::info(&format!(
+        "Upgraded {} files: {} replaced, {} merged with your edits, {} with conflicts. {} already up to date, {} skipped.",
+        replaced + merged + conflicts.len(),
+        replaced,
+        merged,
+        conflicts.len(),
+        up_to_date,
+        skipped
+    ));
+
+    if !conflicts.is_empty() {
+        let paths = conflicts.iter().map(|path| format!("  {}", path)).collect::<Vec<String>>().join("\n");
+        report::error(Code::MergeConflict, &format!("Conflicts in:\n{}\nResolve them and remove the markers.", paths));
+    }
+}
+
+/// Prints a unified diff between what the templates render now and the generated files on disk,
+/// for a file, a folder or a feature, or for the whole project.
+pub fn diff(target: Option<&str>) {
+    let manifest = match Manifest::load() {
+        Ok(manifest) => manifest,
+        Err(e) => {
+            report::error(Code::InvalidInput, &e);
+            return;
+        }
+    };
+
+    // lib/src/utils/ -> lib\src\utils, a feature name -> its lib and test folders
+    let prefixes: Vec<String> = match target.map(|target| target.replace('/', "\\").trim_end_matches('\\').to_string()) {
+        Some(target) if manifest.files.keys().any(|path| path == &target || path.starts_with(&format!("{}\\", target))) => vec![target],
+        Some(feature_name) => vec![feature_path(&feature_name, None), format!("test\\{}", feature_path(&feature_name, None).trim_start_matches("lib\\"))],
+        None => Vec::new(),
+    };
+
+    let files: Vec<_> = manifest
+        .files
+        .iter()
+        .filter(|(path, _)| prefixes.is_empty() || prefixes.iter().any(|prefix| *path == prefix || path.starts_with(&format!("{}\\", prefix))))
+        .collect();
+
+    if files.is_empty() {
+        report::error(Code::NotFound, &format!("No generated file matches '{}'. Only files listed in {} can be compared.", target.unwrap_or_default(), manifest::MANIFEST_PATH));
+        return;
+    }
+
+    let mut changed = 0;
+
+    for (path, entry) in &files {
+        let rendered = match templates::render(&entry.template, &entry.variables) {
+            Some(rendered) => rendered,
+            None => {
+                report::detail(&format!("Skipping {}, it is generated from other project files", path));
+                continue;
+            }
+        };
+
+        let current = match std::fs::read_to_string(path) {
+            Ok(current) => current,
+            Err(_) => {
+                report::detail(&format!("Skipping {}, it was deleted", path));
+                continue;
+            }
+        };
+
+        if current == rendered {
+            continue;
+        }
+
+        let display_path = path.replace('\\', "/");
+        let patch = diffy::DiffOptions::new()
+            .set_original_filename(format!("a/{}", display_path))
+            .set_modified_filename(format!("b/{}", display_path))
+            .create_patch(&rendered, &current)
+            .to_string();
+
+        report::diff(path, &patch);
+        changed += 1;
+    }
+
+    report::info(&format!("{} of {} generated files differ from the templates.", changed, files.len()));
+}
+
+/// Merges the changes between `base` and `theirs` into `ours`. Conflicting changes are kept
+/// between git style markers, returned as the error.
+fn three_way_merge(base: &str, ours: &str, theirs: &str) -> Result<String, String> {
+    let mut options = diffy::MergeOptions::new();
+    options.set_conflict_style(diffy::ConflictStyle::Merge);
+
+    // diffy labels the sides ours and theirs
+    let label = |content: String| -> String {
+        content
+            .split('\n')
+            .map(|line| match line {
+                "<<<<<<< ours" => "<<<<<<< current",
+                ">>>>>>> theirs" => ">>>>>>> upgraded",
+                line => line,
+            })
+            .collect::<Vec<&str>>()
+            .join("\n")
+    };
+
+    // generated files don't end with a newline, which would put the markers on the last line
+    let terminated = |content: &str| if content.ends_with('\n') { content.to_string() } else { format!("{}\n", content) };
+    let restore = |content: String| if ours.ends_with('\n') { content } else { content.strip_suffix('\n').map(str::to_string).unwrap_or(content) };
+
+    match options.merge(&terminated(base), &terminated(ours), &terminated(theirs)) {
+        Ok(content) => Ok(restore(content)),
+        Err(content) => Err(label(content)),
+    }
+}
+
+/// Prints the features (or sub features) of the project, one per line, for the completion scripts.
+/// The sub features are the ones of the feature given with `--features`/`--feature` in `line`, or all of them.
+pub fn complete(kind: &str, line: Option<&str>) {
+    let features = feature_names();
+
+    let names: Vec<String> = match kind {
+        "sub-features" => {
+            let words: Vec<&str> = line.unwrap_or_default().split_whitespace().collect();
+            let parent = words
+                .windows(2)
+                .find(|pair| ["--features", "--feature", "-f"].contains(&pair[0]))
+                .map(|pair| pair[1].split(',').next().unwrap_or_default().to_string())
+                .or_else(|| words.iter().find_map(|word| word.strip_prefix("--features=").or_else(|| word.strip_prefix("--feature="))).map(str::to_string));
+
+            features
+                .into_iter()
+                .filter(|(feature_name, _)| parent.as_ref().is_none_or(|parent| parent == feature_name))
+                .filter_map(|(_, sub_feature_name)| sub_feature_name)
+                .collect()
+        }
+        _ => features.into_iter().filter(|(_, sub_feature_name)| sub_feature_name.is_none()).map(|(feature_name, _)| feature_name).collect(),
+    };
+
+    for name in names {
+        println!("{}", name);
+    }
+}
+
+/// Features of the project with their sub features, e.g. `("orders", Some("checkout"))`.
+pub fn feature_names() -> Vec<(String, Option<String>)> {
+    let folder_names = |path: &str| -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(path)
+            .map(|entries| entries.flatten().filter(|entry| entry.path().is_dir()).map(|entry| entry.file_name().to_string_lossy().to_string()).collect())
+            .unwrap_or_default();
+        names.sort();
+        names
+    };
+
+    let mut features = Vec::new();
+    for feature_name in folder_names("lib\\src\\features") {
+        let sub_feature_names = folder_names(&format!("lib\\src\\features\\{}\\sub_features", feature_name));
+        features.push((feature_name.clone(), None));
+        features.extend(sub_feature_names.into_iter().map(|sub_feature_name| (feature_name.clone(), Some(sub_feature_name))));
+    }
+
+    features
+}
+
+pub fn generate_theme(seed: Option<&str>, theme_mode: Option<&str>) {
+    let (seed, theme_mode) = match theme_settings(seed, theme_mode) {
+        Ok(settings) => settings,
+        Err(e) => {
+            report::error(Code::InvalidArgument, &e);
+            return;
+        }
+    };
+
+    if !check_conflicts(&["lib\\src\\constants\\app_colors.dart".to_string(), "lib\\src\\utils\\theme.dart".to_string()]) {
+        return;
+    }
+
+    create_folder_all("lib\\src\\constants");
+    create_folder_all("lib\\src\\utils");
+
+    generate_file("lib\\src\\constants", "app_colors.dart", "app_colors", json!({ "seed": seed.to_hex() }));
+    generate_file("lib\\src\\utils", "theme.dart", "theme", json!({ "mode": theme_mode }));
+
+    // AppTextStyles reads its colors from AppColors
+    add_import("lib\\src\\constants\\app_text_styles.dart", "import 'app_colors.dart';");
+
+    wire_theme_app();
+}
+
+/// Seed color and theme mode, from the arguments or else the theme section of the project config.
+fn theme_settings(seed: Option<&str>, theme_mode: Option<&str>) -> Result<(theme::Rgb, String), String> {
+    let theme = ProjectConfig::load()?.theme.unwrap_or_default();
+
+    let seed = seed.map(str::to_string).or(theme.seed).unwrap_or_else(|| theme::DEFAULT_SEED.to_string());
+    let theme_mode = theme_mode.map(str::to_string).or(theme.mode).unwrap_or_else(|| "system".to_string());
+
+    if !["system", "light", "dark"].contains(&theme_mode.as_str()) {
+        return Err(format!("Invalid theme mode '{}'. Expected system, light or dark.", theme_mode));
+    }
+
+    Ok((theme::parse_hex_color(&seed)?, theme_mode))
+}
+
+/// Replaces the inline ThemeData of the MaterialApp with AppTheme and the theme mode provider.
+fn wire_theme_app() {
+    let app_path = "lib\\src\\app.dart";
+    let content = match std::fs::read_to_string(app_path) {
+        Ok(content) => content,
+        Err(_) => return,
+    };
+
+    if content.contains("AppTheme.light") {
+        return;
+    }
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let start = lines.iter().position(|line| line.trim_start().starts_with("theme: ThemeData("));
+
+    // find the line closing the ThemeData
+    let end = start.and_then(|start| {
+        let mut depth = 0;
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            for c in line.chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => (),
+                }
+            }
+            if depth == 0 {
+                return Some(index);
+            }
+        }
+        None
+    });
+
+    let (start, end) = match (start, end) {
+        (Some(start), Some(end)) => (start, end),
+        _ => {
+            report::warning(Code::ManualStep, &format!("Unable to find the ThemeData of {}. Set theme: AppTheme.light, darkTheme: AppTheme.dark and themeMode: ref.watch(themeModeProvider) manually.", app_path));
+            return;
+        }
+    };
+
+    let indent = leading_whitespace(&lines[start]).to_string();
+    lines.splice(
+        start..=end,
+        ["theme: AppTheme.light,", "darkTheme: AppTheme.dark,", "themeMode: ref.watch(themeModeProvider),"].iter().map(|line| format!("{}{}", indent, line)),
+    );
+
+    write_file(app_path, format!("{}\n", lines.join("\n")));
+    add_import(app_path, "import 'utils/theme.dart';");
+    report::info(&format!("Using AppTheme in {}", app_path));
+}
+
+pub fn import_tokens(tokens_path: &str) {
+    let content = match std::fs::read_to_string(tokens_path) {
+        Ok(content) => content,
+        Err(e) => {
+            report::error(Code::NotFound, &format!("Unable to read {}: {}", tokens_path, e));
+            return;
+        }
+    };
+
+    let tokens = match tokens::parse_tokens(&content) {
+        Ok(tokens) => tokens,
+        Err(e) => {
+            report::error(Code::InvalidInput, &e);
+            return;
+        }
+    };
+
+    let seed = match theme_settings(None, None) {
+        Ok((seed, _)) => seed,
+        Err(e) => {
+            report::warning(Code::InvalidInput, &format!("{} Using the default seed color.", e));
+            theme::parse_hex_color(theme::DEFAULT_SEED).expect("Invalid default seed")
+        }
+    };
+
+    let text_color = ["kText", "text", "textPrimary"]
+        .iter()
+        .find(|name| tokens.colors.iter().any(|color| color.name == **name))
+        .map(|name| format!("AppColors.{}", name));
+
+    // (file, class the constants are read through, template, generated content)
+    let mut files = Vec::new();
+    if !tokens.colors.is_empty() {
+        files.push(("app_colors.dart", "AppColors.", "token_colors", token_colors(&tokens.colors, &theme::palette(seed))));
+    }
+    if !tokens.sizes.is_empty() {
+        files.push(("app_sizes.dart", "Sizes.", "token_sizes", token_sizes(&tokens.sizes)));
+    }
+    if !tokens.text_styles.is_empty() {
+        files.push(("app_text_styles.dart", "AppTextStyles.", "token_text_styles", token_text_styles(&tokens.text_styles, text_color.as_deref())));
+    }
+
+    if files.is_empty() {
+        report::error(Code::InvalidInput, &format!("No color, size or typography tokens found in {}.", tokens_path));
+        return;
+    }
+
+    if !check_conflicts(&files.iter().map(|(file_name, _, _, _)| format!("lib\\src\\constants\\{}", file_name)).collect::<Vec<String>>()) {
+        return;
+    }
+
+    create_folder_all("lib\\src\\constants");
+
+    let mut removed: Vec<String> = Vec::new();
+
+    for (file_name, class, template, content) in &files {
+        let path = format!("lib\\src\\constants\\{}", file_name);
+        let previous = dart_constants(&std::fs::read_to_string(&path).unwrap_or_default());
+        let current = dart_constants(content);
+
+        for name in previous.iter().filter(|name| !current.contains(name)) {
+            // gaps are top level constants, everything else is read through its class
+            removed.push(if name.starts_with("gap") { name.clone() } else { format!("{}{}", class, name) });
+        }
+
+        create_generated_file("lib\\src\\constants", file_name, template, json!({ "tokens": tokens_path }), content.clone());
+    }
+
+    report::info(&format!(
+        "Imported {} colors, {} sizes and {} text styles from {}",
+        tokens.colors.len(),
+        tokens.sizes.len(),
+        tokens.text_styles.len(),
+        tokens_path
+    ));
+
+    warn_removed_references(&removed, &files.iter().map(|(file_name, _, _, _)| *file_name).collect::<Vec<&str>>());
+}
+
+/// Names of the `static const` and top level `const` declarations of a Dart file.
+fn dart_constants(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("static const ").or_else(|| line.trim().strip_prefix("const ")))
+        .filter_map(|declaration| declaration.split(|c: char| c == '=' || c.is_whitespace()).next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Warns about removed constants that code under lib/ still references.
+fn warn_removed_references(removed: &[String], regenerated: &[&str]) {
+    if removed.is_empty() {
+        return;
+    }
+
+    for path in dart_files(Path::new("lib")) {
+        let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        if regenerated.iter().any(|regenerated| file_name.ends_with(regenerated)) {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap_or_default();
+        for name in removed.iter().filter(|name| references(&content, name)) {
+            report::warning(Code::StaleReference, &format!("{} was removed but is still used in {}.", name, path.display()));
+        }
+    }
+}
+
+/// Whether `content` uses the identifier `name`, e.g. `AppColors.primary` but not `AppColors.primaryDark`.
+fn references(content: &str, name: &str) -> bool {
+    let is_identifier = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+
+    content.match_indices(name).any(|(index, _)| {
+        let before = content[..index].chars().next_back();
+        let after = content[index + name.len()..].chars().next();
+        !before.is_some_and(|c| is_identifier(c) || c == '.') && !after.is_some_and(is_identifier)
+    })
+}
+
+/// Dart files in a folder and its sub folders.
+fn dart_files(folder: &Path) -> Vec<std::path::PathBuf> {
+    let mut files = Vec::new();
+
+    if let Ok(entries) = std::fs::read_dir(folder) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(dart_files(&path));
+            } else if path.extension().is_some_and(|extension| extension == "dart") {
+                files.push(path);
+            }
+        }
+    }
+
+    files
+}
+
+/// Turns main.dart into the bootstrap shared by the flavor entry points.
+fn bootstrap_from_main(main: &str) -> String {
+    main.replace("void main() async {", "Future<void> bootstrap(Flavor flavor) async {")
+        .replace("dotenv.load(fileName: '.env')", "dotenv.load(fileName: '.env.${flavor.name}')")
+        .replace("import 'src/", "import '")
+}
+
+/// Values of the Flavor enum in constants/flavor.dart.
+fn existing_flavors() -> Vec<String> {
+    let content = std::fs::read_to_string("lib\\src\\constants\\flavor.dart").unwrap_or_default();
+
+    content
+        .split_once("enum Flavor {")
+        .and_then(|(_, rest)| rest.split_once('}'))
+        .map(|(values, _)| values.split(',').map(|value| value.trim().to_string()).filter(|value| !value.is_empty()).collect())
+        .unwrap_or_default()
+}
+
+/// Env files of the flavors, e.g. `.env.dev`.
+fn flavor_env_files() -> Vec<String> {
+    existing_flavors().iter().map(|flavor| format!(".env.{}", flavor)).filter(|path| Path::new(path).exists()).collect()
+}
+
+/// Adds files to the assets of the flutter section of pubspec.yaml.
+pub fn add_pubspec_assets(assets: &[String]) {
+    let content = match std::fs::read_to_string("pubspec.yaml") {
+        Ok(content) => content,
+        Err(_) => {
+            report::warning(Code::ManualStep, &format!("pubspec.yaml does not exist. Add {} to the flutter assets manually.", assets.join(", ")));
+            return;
+        }
+    };
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let (flutter, section_end) = flutter_section(&mut lines);
+
+    let assets_line = lines[flutter + 1..section_end].iter().position(|line| line.trim_end() == "  assets:").map(|offset| flutter + 1 + offset);
+
+    let mut insert_at = match assets_line {
+        Some(assets_line) => lines[assets_line + 1..section_end]
+            .iter()
+            .position(|line| !line.trim_start().starts_with("- "))
+            .map_or(section_end, |offset| assets_line + 1 + offset),
+        None => {
+            lines.insert(flutter + 1, "  assets:".to_string());
+            flutter + 2
+        }
+    };
+
+    for asset in assets {
+        if lines.iter().any(|line| line.trim() == format!("- {}", asset)) {
+            continue;
+        }
+        lines.insert(insert_at, format!("    - {}", asset));
+        insert_at += 1;
+        report::info(&format!("Adding {} to the pubspec.yaml assets", asset));
+    }
+
+    write_file("pubspec.yaml", format!("{}\n", lines.join("\n")));
+}
+
+/// Sets a key of the flutter section of pubspec.yaml, e.g. `generate: true`.
+fn set_pubspec_flutter_option(key: &str, value: &str) {
+    let content = match std::fs::read_to_string("pubspec.yaml") {
+        Ok(content) => content,
+        Err(_) => {
+            report::warning(Code::ManualStep, &format!("pubspec.yaml does not exist. Add `{}: {}` to its flutter section manually.", key, value));
+            return;
+        }
+    };
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let (flutter, section_end) = flutter_section(&mut lines);
+    let option = format!("  {}: {}", key, value);
+
+    match lines[flutter + 1..section_end].iter().position(|line| line.starts_with(&format!("  {}:", key))) {
+        Some(offset) => lines[flutter + 1 + offset] = option,
+        None => lines.insert(flutter + 1, option),
+    }
+
+    write_file("pubspec.yaml", format!("{}\n", lines.join("\n")));
+}
+
+/// Start and end of the top level flutter section of pubspec.yaml lines, added if missing.
+fn flutter_section(lines: &mut Vec<String>) -> (usize, usize) {
+    let flutter = match lines.iter().position(|line| line.trim_end() == "flutter:") {
+        Some(flutter) => flutter,
+        None => {
+            lines.push("flutter:".to_string());
+            lines.len() - 1
+        }
+    };
+
+    // the flutter section ends at the next top level key
+    let section_end = lines[flutter + 1..]
+        .iter()
+        .position(|line| !line.is_empty() && !line.starts_with(' ') && !line.starts_with('#'))
+        .map_or(lines.len(), |offset| flutter + 1 + offset);
+
+    (flutter, section_end)
+}
+
+/// Adds the Dio based http client service used by generated remote repositories if missing,
+/// together with its base url in .env.
+fn ensure_http_client_service(base_url: Option<&str>) {
+    if !Path::new("lib\\src\\services\\http_client_service.dart").exists() {
+        add_service("http_client");
+    }
+
+    let env = std::fs::read_to_string(".env").unwrap_or_default();
+    if !env.lines().any(|line| line.starts_with("API_BASE_URL=")) {
+        let separator = if env.is_empty() || env.ends_with('\n') { "" } else { "\n" };
+        write_file(".env", format!("{}{}API_BASE_URL={}\n", env, separator, base_url.unwrap_or_default()));
+    }
+}
+
+struct ServiceTemplate {
+    kind: &'static str,
+    template: fn() -> String,
+    dependencies: &'static [&'static str],
+}
+
+/// Built-in services for `rudder add service`. Any other name generates a blank service.
+const SERVICE_TEMPLATES: [ServiceTemplate; 6] = [
+    ServiceTemplate { kind: "http_client", template: http_client_service, dependencies: &["dio"] },
+    ServiceTemplate { kind: "secure_storage", template: secure_storage_service, dependencies: &["flutter_secure_storage"] },
+    ServiceTemplate { kind: "shared_preferences", template: shared_preferences_service, dependencies: &["shared_preferences"] },
+    ServiceTemplate { kind: "connectivity", template: connectivity_service, dependencies: &["connectivity_plus"] },
+    ServiceTemplate { kind: "biometrics", template: biometrics_service, dependencies: &["local_auth"] },
+    ServiceTemplate { kind: "analytics", template: analytics_service, dependencies: &["firebase_core", "firebase_analytics"] },
+];
+
+/// Content of a service, from its built-in template or blank.
+fn service_content(name: &str) -> String {
+    match SERVICE_TEMPLATES.iter().find(|template| template.kind == name) {
+        Some(template) => (template.template)(),
+        None => blank_service(name),
+    }
+}
+
+/// Kinds of services with a built-in template.
+fn service_kinds() -> Vec<&'static str> {
+    SERVICE_TEMPLATES.iter().map(|template| template.kind).collect()
+}
+
+/// `http`, `http-client` and `httpClient` all select the http client, `logger_service` is `logger`.
+fn service_name(service_name: &str) -> String {
+    let name = match camel_case(service_name).as_str() {
+        "http" => "http_client".to_string(),
+        _ => words_to_snake_case(service_name),
+    };
+
+    name.trim_end_matches("_service").to_string()
+}
+
+pub fn add_service(service_name: &str) {
+    let name = self::service_name(service_name);
+    let services = "lib\\src\\services";
+    let file_name = format!("{}_service.dart", name);
+
+    if !check_conflicts(&[format!("{}\\{}", services, file_name)]) {
+        return;
+    }
+
+    if !Path::new(services).exists() {
+        create_folder(services);
+    }
+
+    let template = SERVICE_TEMPLATES.iter().find(|template| template.kind == name);
+
+    generate_file(services, &file_name, "service", json!({ "name": name }));
+
+    register_service_override(&name, &format!("{}Service", pascal_case(&name)));
+
+    if let Some(template) = template {
+        ensure_dependencies(template.dependencies, &[]);
+    }
+}
+
+/// Splits a comma separated flag or answer, e.g. `home, orders` -> `["home", "orders"]`.
+pub fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|value| !value.is_empty()).map(str::to_string).collect()
+}
+
+/// `secureStorage`, `secure-storage` and `Secure Storage` all become `secure_storage`.
+fn words_to_snake_case(value: &str) -> String {
+    let mut snake = String::new();
+
+    for c in value.chars() {
+        if c.is_ascii_uppercase() && !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+
+        if c.is_ascii_alphanumeric() {
+            snake.push(c.to_ascii_lowercase());
+        } else if !snake.is_empty() && !snake.ends_with('_') {
+            snake.push('_');
+        }
+    }
+
+    snake.trim_end_matches('_').to_string()
+}
+
+pub fn add_widget(widget_name: &str, feature_name: Option<&str>, sub_feature_name: Option<&str>, kind: &str) {
+    let class_name = pascal_case(widget_name);
+    let file_name = words_to_snake_case(widget_name);
+
+    if class_name.is_empty() {
+        report::error(Code::InvalidArgument, &format!("Invalid widget name '{}'.", widget_name));
+        return;
+    }
+
+    // shared widgets live next to the features, feature widgets next to their screen
+    let (widgets_path, barrel_name) = match feature_name {
+        Some(feature_name) => {
+            let base_path = feature_path(feature_name, sub_feature_name);
+            if !Path::new(&base_path).exists() {
+                report::error(Code::NotFound, &format!("{} does not exist. Add the feature first with: rudder add --features {}", base_path, feature_name));
+                return;
+            }
+            (format!("{}\\presentation\\widgets", base_path), "widgets.dart")
+        }
+        None => ("lib\\src\\shared_widgets".to_string(), "shared_widgets.dart"),
+    };
+
+    let widget_path = format!("{}\\{}.dart", widgets_path, file_name);
+
+    // lib\\src\\shared_widgets -> test\\src\\shared_widgets
+    let test_path = format!("test\\{}", widgets_path.trim_start_matches("lib\\"));
+    if !check_conflicts(&[widget_path.clone(), format!("{}\\{}_test.dart", test_path, file_name)]) {
+        return;
+    }
+
+    create_folder_all(&widgets_path);
+    generate_file(&widgets_path, &format!("{}.dart", file_name), "widget", json!({ "class_name": class_name, "kind": kind }));
+    add_barrel_export(&format!("{}\\{}", widgets_path, barrel_name), &format!("{}.dart", file_name));
+
+    let package_name = match package_name() {
+        Some(package_name) => package_name,
+        None => {
+            report::warning(Code::NotFound, "Unable to read the package name from pubspec.yaml. Skipping the widget test.");
+            return;
+        }
+    };
+
+    // lib\src\shared_widgets\avatar.dart -> src/shared_widgets/avatar.dart
+    let widget_import = widget_path.trim_start_matches("lib\\").replace('\\', "/");
+
+    create_folder_all(&test_path);
+    generate_file(
+        &test_path,
+        &format!("{}_test.dart", file_name),
+        "widget_test",
+        json!({ "package_name": package_name, "widget_import": widget_import, "class_name": class_name, "kind": kind }),
+    );
+}
+
+/// Adds an export to a barrel file, keeping the exports sorted.
+fn add_barrel_export(barrel_path: &str, export_path: &str) {
+    let content = std::fs::read_to_string(barrel_path).unwrap_or_default();
+    let export = format!("export '{}';", export_path);
+
+    let mut exports: Vec<String> = content.lines().filter(|line| line.trim().starts_with("export ")).map(|line| line.trim().to_string()).collect();
+    if exports.contains(&export) {
+        return;
+    }
+    exports.push(export);
+    exports.sort();
+
+    // keep anything else the barrel declares, e.g. a library directive
+    let other: Vec<&str> = content.lines().filter(|line| !line.trim().starts_with("export ") && !line.trim().is_empty()).collect();
+    let mut lines: Vec<String> = other.iter().map(|line| line.to_string()).collect();
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.extend(exports);
+
+    report::info(&format!("Exporting {} from {}", export_path, barrel_path));
+    write_file(barrel_path, format!("{}\n", lines.join("\n")));
+}
+
+/// File creating the ProviderContainer, with the import prefix leading from it to lib/src.
+/// That's main.dart, or the bootstrap shared by the flavor entry points once flavors are added.
+pub fn entry_point() -> (String, String) {
+    if Path::new("lib\\src\\bootstrap.dart").exists() {
+        ("lib\\src\\bootstrap.dart".to_string(), String::new())
+    } else {
+        ("lib\\main.dart".to_string(), "src/".to_string())
+    }
+}
+
+/// Imports a service in the entry point and overrides its provider in the ProviderContainer,
+/// the same way the logger service is registered.
+pub fn register_service_override(service_name: &str, class_name: &str) {
+    let provider = format!("{}Provider", class_name[..1].to_lowercase() + &class_name[1..]);
+    let (entry_path, entry_import) = entry_point();
+    let entry_path = entry_path.as_str();
+
+    if !add_import(entry_path, &format!("import '{}services/{}_service.dart';", entry_import, service_name)) {
+        report::warning(Code::ManualStep, &format!("Unable to register {} in {}. Add the provider override manually.", class_name, entry_path));
+        return;
+    }
+
+    // a service added again is registered once
+    let content = std::fs::read_to_string(entry_path).unwrap_or_default();
+    let override_line = format!("{}.overrideWith((ref) => {}(ref)),", provider, class_name);
+    let init_line = format!("await container.read({}).init();", provider);
+
+    let registered = (content.contains(&override_line) || insert_after_line(entry_path, "overrides: [", &override_line))
+        && (content.contains(&init_line) || insert_after_line(entry_path, "await container.read(loggerServiceProvider).init();", &init_line));
+
+    if !registered {
+        report::warning(Code::ManualStep, &format!("Unable to register {} in {}. Add the provider override manually.", class_name, entry_path));
+    }
+}
+
+/// Adds an import below the last import of a Dart file. Returns false if the file can't be read.
+pub fn add_import(path: &str, import: &str) -> bool {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return false,
+    };
+
+    if content.contains(import) {
+        return true;
+    }
+
+    let mut lines: Vec<&str> = content.lines().collect();
+    let index = lines.iter().rposition(|line| line.trim_start().starts_with("import ")).map_or(0, |index| index + 1);
+    let indent = if index > 0 { leading_whitespace(lines[index - 1]) } else { "" };
+    let line = format!("{}{}", indent, import);
+    lines.insert(index, &line);
+
+    write_lines(path, &lines, content.ends_with('\n'));
+    true
+}
+
+/// Inserts `text` on a new line after the first line containing `anchor`, indented one level
+/// deeper than the anchor when it opens a block. Returns false if the anchor is missing.
+pub fn insert_after_line(path: &str, anchor: &str, text: &str) -> bool {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return false,
+    };
+
+    let mut lines: Vec<&str> = content.lines().collect();
+
+    let index = match lines.iter().position(|line| line.contains(anchor)) {
+        Some(index) => index,
+        None => return false,
+    };
+
+    if lines.get(index + 1).is_some_and(|line| line.trim() == text.trim()) {
+        return true;
+    }
+
+    let extra = if lines[index].trim_end().ends_with(['[', '{', '(']) { "    " } else { "" };
+    let line = format!("{}{}{}", leading_whitespace(lines[index]), extra, text);
+    lines.insert(index + 1, &line);
+
+    write_lines(path, &lines, content.ends_with('\n'));
+    true
+}
+
+fn write_lines(path: &str, lines: &[&str], trailing_newline: bool) -> bool {
+    let mut content = lines.join("\n");
+    if trailing_newline {
+        content.push('\n');
+    }
+    write_file(path, content)
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start().len()]
+}
+
+pub fn add_exception(feature_name: &str, sub_feature_name: Option<&str>, exception_name: &str, message: &str) {
+    let app_exceptions = "lib\\src\\exceptions\\app_exceptions.dart";
+
+    let content = match std::fs::read_to_string(app_exceptions) {
+        Ok(content) => content,
+        Err(_) => {
+            report::error(Code::NotFound, &format!("{} does not exist. Run rudder create first.", app_exceptions));
+            return;
+        }
+    };
+
+    let name = camel_case(exception_name);
+    let class_name = pascal_case(exception_name);
+
+    if content.contains(&format!("AppException.{}(", name)) {
+        report::error(Code::AlreadyExists, &format!("AppException.{} already exists. Skipping.", name));
+        return;
+    }
+
+    let factory = format!("const factory AppException.{}() = {};", name, class_name);
+    let message = format!("{}: () => '{}',", name, message.replace('\\', "\\\\").replace('\'', "\\'"));
+
+    let lines: Vec<&str> = content.lines().collect();
+    let factory_index = lines.iter().rposition(|line| line.contains("const factory AppException."));
+    let when_index = lines.iter().position(|line| line.contains("return when("));
+    let close_index = when_index.and_then(|start| lines[start..].iter().position(|line| line.trim_start().starts_with(");")).map(|offset| start + offset));
+
+    let (factory_index, when_index, close_index) = match (factory_index, when_index, close_index) {
+        (Some(factory_index), Some(when_index), Some(close_index)) => (factory_index, when_index, close_index),
+        _ => {
+            report::error(Code::ManualStep, &format!("Unable to find the AppException union in {}. Add the case manually.", app_exceptions));
+            return;
+        }
+    };
+
+    let case_indent = if close_index > when_index + 1 {
+        leading_whitespace(lines[close_index - 1]).to_string()
+    } else {
+        format!("{}    ", leading_whitespace(lines[when_index]))
+    };
+
+    let factory = format!("{}{}", leading_whitespace(lines[factory_index]), factory);
+    let message = format!("{}{}", case_indent, message);
+
+    // insert the later line first so the earlier index stays valid
+    let mut updated = lines.clone();
+    updated.insert(close_index, &message);
+    updated.insert(factory_index + 1, &factory);
+
+    write_lines(app_exceptions, &updated, content.ends_with('\n'));
+    report::info(&format!("Adding AppException.{} to {}", name, app_exceptions));
+
+    // group the case under the feature it belongs to
+    let feature_file_name = sub_feature_name.unwrap_or(feature_name);
+    let domain = format!("{}\\domain", feature_path(feature_name, sub_feature_name));
+    let feature_exceptions_path = format!("{}\\{}_exceptions.dart", domain, feature_file_name);
+
+    if !Path::new(&domain).exists() {
+        report::warning(Code::NotFound, &format!("{} does not exist. AppException.{} was added without a feature.", domain, name));
+        return;
+    }
+
+    if !Path::new(&feature_exceptions_path).exists() {
+        generate_file(
+            &domain,
+            &format!("{}_exceptions.dart", feature_file_name),
+            "feature_exceptions",
+            json!({ "feature": feature_file_name, "exceptions_import": exceptions_import(sub_feature_name.is_some()) }),
+        );
+    }
+
+    let feature_class = format!("abstract class {}Exceptions {{", feature_file_name.to_string().to_pascal_case());
+    if !insert_after_line(&feature_exceptions_path, &feature_class, &format!("static const {} = AppException.{}();", name, name)) {
+        report::warning(Code::ManualStep, &format!("Unable to find {} in {}.", feature_class, feature_exceptions_path));
+    }
+}
+
+pub fn add_method(feature_name: &str, sub_feature_name: Option<&str>, method_name: &str, returns: &str, params: Option<&str>, repository: &str) {
+    let params = match params.map(parse_fields).transpose() {
+        Ok(params) => params.unwrap_or_default(),
+        Err(e) => {
+            report::error(Code::InvalidArgument, &e);
+            return;
+        }
+    };
+
+    let base_path = feature_path(feature_name, sub_feature_name);
+
+    if !Path::new(&base_path).exists() {
+        report::error(Code::NotFound, &format!("{} does not exist. Add the feature first with: rudder add --features {}", base_path, feature_name));
+        return;
+    }
+
+    let file_name = sub_feature_name.unwrap_or(feature_name);
+    let pascal_name = file_name.to_string().to_pascal_case();
+    let repository_name = repository.to_string().capitalize();
+    let name = camel_case(method_name);
+    let signature = method_signature(&name, returns, &params);
+    let arguments = method_arguments(&params);
+    let needs_models = uses_custom_types(returns) || params.iter().any(|param| uses_custom_types(&param.dart_type));
+
+    let repository_path = format!("{}\\data\\{}_{}_repository.dart", base_path, file_name, repository);
+    let fake_path = format!("{}\\data\\fake_{}_{}_repository.dart", base_path, file_name, repository);
+    let service_path = format!("{}\\application\\{}_service.dart", base_path, file_name);
+    let controller_path = format!("{}\\presentation\\controllers\\{}_controller.dart", base_path, file_name);
+
+    let fake_body = match (returns, dart_default_value(returns)) {
+        ("void", _) => "{}".to_string(),
+        (_, Some(value)) => format!("=> {};", value),
+        (_, None) => "=> throw UnimplementedError();".to_string(),
+    };
+
+    let additions: Vec<(&str, String, Vec<String>, Vec<String>)> = vec![
+        (&repository_path, format!("abstract class Abstract{}{}Repository", pascal_name, repository_name), vec![format!("{};", signature)], vec![]),
+        (
+            &repository_path,
+            format!("class {}{}Repository implements", pascal_name, repository_name),
+            vec!["@override".to_string(), format!("{} async => throw UnimplementedError();", signature)],
+            vec!["import '../domain/{file}_models.dart';".to_string()],
+        ),
+        (
+            &fake_path,
+            format!("class Fake{}{}Repository implements", pascal_name, repository_name),
+            vec!["@override".to_string(), format!("{} async {}", signature, fake_body)],
+            vec!["import '../domain/{file}_models.dart';".to_string()],
+        ),
+        (&service_path, format!("abstract class Abstract{}Service", pascal_name), vec![format!("{};", signature)], vec![]),
+        (
+            &service_path,
+            format!("class {}Service implements", pascal_name),
+            vec![
+                "@override".to_string(),
+                format!("{} => ref.read({}{}RepositoryProvider).{}({});", signature, pascal_name.to_lowercase(), repository_name, name, arguments),
+            ],
+            vec!["import '../domain/{file}_models.dart';".to_string(), format!("import '../data/{{file}}_{}_repository.dart';", repository)],
+        ),
+        (
+            &controller_path,
+            format!("class {}Controller ", pascal_name),
+            vec![
+                format!("{} async {{", method_signature(&name, "void", &params)),
+                format!("    await ref.read({}ServiceProvider).{}({});", pascal_name.to_lowercase(), name, arguments),
+                "}".to_string(),
+            ],
+            vec!["import '../../domain/{file}_models.dart';".to_string(), "import '../../application/{file}_service.dart';".to_string()],
+        ),
+    ];
+
+    if std::fs::read_to_string(&repository_path).unwrap_or_default().contains(&format!(" {}(", name)) {
+        report::error(Code::AlreadyExists, &format!("{} already declares {}.", repository_path, name));
+        return;
+    }
+
+    let mut updated_paths: Vec<&str> = Vec::new();
+
+    for (path, class_prefix, member, imports) in additions {
+        if !insert_into_class(path, &class_prefix, &member) {
+            report::warning(Code::ManualStep, &format!("Unable to find `{}` in {}. Add {} manually.", class_prefix, path, name));
+            continue;
+        }
+
+        for import in imports {
+            let import = import.replace("{file}", file_name);
+            if import.contains("_models.dart") && !needs_models {
+                continue;
+            }
+            add_import(path, &import);
+        }
+
+        if !updated_paths.contains(&path) {
+            report::info(&format!("Adding {} to {}", name, path));
+            updated_paths.push(path);
+        }
+    }
+}
+
+/// Inserts `member` as the last member of the class whose declaration line contains `class_prefix`.
+pub fn insert_into_class(path: &str, class_prefix: &str, member: &[String]) -> bool {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(_) => return false,
+    };
+
+    let lines: Vec<&str> = content.lines().collect();
+
+    let start = match lines.iter().position(|line| line.contains(class_prefix)) {
+        Some(start) => start,
+        None => return false,
+    };
+
+    // find the line closing the class body
+    let mut depth = 0;
+    let mut opened = false;
+    let mut end = None;
+
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        for c in line.chars() {
+            match c {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' => depth -= 1,
+                _ => (),
+            }
+        }
+
+        if opened && depth == 0 {
+            end = Some(index);
+            break;
+        }
+    }
+
+    let end = match end {
+        Some(end) => end,
+        None => return false,
+    };
+
+    let indent = format!("{}    ", leading_whitespace(lines[start]));
+    let mut inserted: Vec<String> = Vec::new();
 
-    let layers = ["application", "data", "domain", "presentation"];
+    // a class declared on a single line, e.g. `class A {}`
+    if end == start {
+        return false;
+    }
+
+    if !lines[end - 1].trim().is_empty() && !lines[end - 1].trim_end().ends_with('{') {
+        inserted.push(String::new());
+    }
+    inserted.extend(member.iter().map(|line| format!("{}{}", indent, line)));
+
+    let mut updated: Vec<&str> = lines[..end].to_vec();
+    updated.extend(inserted.iter().map(String::as_str));
+    updated.extend(&lines[end..]);
+
+    write_lines(path, &updated, content.ends_with('\n'));
+    true
+}
+
+/// Relative import of app_exceptions.dart from a feature's domain folder.
+fn exceptions_import(is_sub_feature: bool) -> String {
+    let depth = if is_sub_feature { 5 } else { 3 };
+    format!("{}exceptions/app_exceptions.dart", "../".repeat(depth))
+}
 
-    let base_path = match sub_feature_name {
+fn l10n_import(is_sub_feature: bool) -> String {
+    let depth = if is_sub_feature { 5 } else { 3 };
+    format!("{}l10n/l10n.dart", "../".repeat(depth))
+}
+
+/// Path of a feature folder, or of a sub feature folder when given.
+pub fn feature_path(feature_name: &str, sub_feature_name: Option<&str>) -> String {
+    match sub_feature_name {
         Some(sub_feature_name) => format!("lib\\src\\features\\{}\\sub_features\\{}", feature_name, sub_feature_name),
         None => format!("lib\\src\\features\\{}", feature_name),
+    }
+}
+
+/// Creates the project structure, or adds the missing parts of it to an existing project.
+pub fn create_project(interactive: bool, answers: ProjectConfig) {
+    // keep the settings of other commands, e.g. the theme
+    let saved = ProjectConfig::load().unwrap_or_default();
+    let mut config = ProjectConfig { theme: saved.theme, other: saved.other, ..answers };
+    if config.preset.is_none() {
+        config.preset = saved.preset;
+    }
+
+    // answers that weren't given default to what's already in pubspec.yaml
+    let pubspec_name = package_name();
+    if config.package_name.is_none() {
+        config.package_name = pubspec_name.clone();
+    }
+    if config.app_title.is_none() {
+        config.app_title = Some(app_title_from_package(config.package_name.as_deref().unwrap_or("app")));
+    }
+
+    // the wizard would mix its prompts into the JSON events
+    let is_terminal = std::io::stdin().is_terminal() && std::io::stdout().is_terminal() && !report::is_json();
+
+    if interactive && is_terminal {
+        config = match wizard::ask(config) {
+            Ok(config) => config,
+            Err(e) => {
+                report::error(Code::Io, &e);
+                return;
+            }
+        };
+    } else if interactive {
+        report::info("Not running in a terminal, reading the answers from the flags.");
+    }
+
+    if let Err(e) = config.validate() {
+        report::error(Code::InvalidArgument, &e);
+        return;
+    }
+
+    let preset = match presets::find(config.preset.as_deref().unwrap_or(presets::DEFAULT_PRESET)) {
+        Ok(preset) => preset,
+        Err(e) => {
+            report::error(Code::InvalidArgument, &e);
+            return;
+        }
     };
 
-    // create [feature_name] folder
-    create_folder(&base_path);
+    // services of the preset come with the ones asked for
+    for service in &preset.services {
+        if !config.services.contains(service) {
+            config.services.push(service.clone());
+        }
+    }
 
-     // Create sub_features folder if doesnt exist
-    if !Path::new(format!("lib\\src\\features\\{}\\sub_features", feature_name).as_str()).exists() {
-        create_folder(format!("lib\\src\\features\\{}\\sub_features", feature_name).as_str());
+    report::info(&wizard::summary(&config));
+
+    if interactive && is_terminal && !wizard::confirm("Create the project?") {
+        report::info("Nothing was written.");
+        return;
     }
 
-    let feature_name: &str = match sub_feature_name {
-        Some(sub_feature_name) => sub_feature_name,
-        None => feature_name,
-    }; 
+    // existing files are kept unless a policy was given
+    let existing_project = Path::new("lib\\src").exists() || Path::new("lib/src").exists();
+    let explicit_policy = *CONFLICT_POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    set_conflict_policy(explicit_policy.unwrap_or(ConflictPolicy::Skip));
 
-    // create folders for layers
-    for &layer in layers.iter() {
-        let path = format!("{base_path}\\{}", layer);
+    if !check_conflicts(&project_file_paths(&config, &preset)) {
+        return;
+    }
 
-        create_folder(&path);
+    create_folder_all(".rudder");
+    let config_existed = Path::new(config::CONFIG_PATH).exists();
+    if let Err(e) = config.save() {
+        report::error(Code::Io, &e);
+        return;
+    }
+    report::file(config::CONFIG_PATH, if config_existed { FileStatus::Updated } else { FileStatus::Created });
 
-        match layer {
-            "application" => {
-                create_file(&path, &format!("{}_service.dart", feature_name), Some(application_service(feature_name)));
-            }
-            "data" => {
-                create_file(&path, &format!("{}_local_repository.dart", feature_name), Some(local_repository(feature_name)));
-                create_file(&path, &format!("{}_remote_repository.dart", feature_name), Some(remote_repository(feature_name)));
+    if let (Some(package_name), Some(pubspec_name)) = (&config.package_name, &pubspec_name) {
+        if package_name != pubspec_name {
+            rename_package(package_name);
+        }
+    }
 
-                // fake repositories
-                create_file(&path, &format!("fake_{}_local_repository.dart", feature_name), Some(fake_local_repository(feature_name)));
-                create_file(&path, &format!("fake_{}_remote_repository.dart", feature_name), Some(fake_remote_repository(feature_name)));
-            }
-            "domain" => {
-                create_file(&path, &format!("{}_models.dart", feature_name), None);
-                create_file(&path, &format!("{}_unions.dart", feature_name), None);
-            }
-            "presentation" => {
-                create_file(&path, format!("{}_screen.dart", feature_name).as_str(), Some(stateless_widget(feature_name)));
-                create_folder(format!("{}\\controllers", &path).as_str());
-                create_file(&path, &format!("controllers\\{}_controller.dart", feature_name), Some(controller(feature_name)));
-            }
-            _ => (),
+    KEEP_REGENERATED_FILES.store(true, Ordering::Relaxed);
+
+    if existing_project {
+        report::info("lib/src already exists. Adding the missing folders and files...");
+    } else {
+        report::info("Creating project structure...");
+    }
+    create_project_structure(&config, &preset, explicit_policy.unwrap_or(ConflictPolicy::Backup));
+    report::info("Project structure created successfully!");
+
+    for service in &config.services {
+        let file_name = format!("lib\\src\\services\\{}_service.dart", service_name(service));
+        if Path::new(&file_name).exists() {
+            report::file(&file_name, FileStatus::Skipped);
+            continue;
         }
+        add_service(service);
+    }
 
-        
+    add_preset_files(&config, &preset);
+
+    if preset.has(presets::Part::Theme) {
+        generate_theme(None, None);
+    }
+
+    if preset.has(presets::Part::L10n) && !preset.locales.is_empty() {
+        init_l10n(&preset.locales.join(","));
+    }
+
+    if preset.has(presets::Part::Flavors) && !preset.flavors.is_empty() {
+        add_flavors(&preset.flavors.join(","));
+    }
+
+    if config.install_dependencies {
+        add_dependencies(&config);
+        ensure_dependencies(&preset.dependencies.iter().map(String::as_str).collect::<Vec<&str>>(), &preset.dev_dependencies.iter().map(String::as_str).collect::<Vec<&str>>());
+    } else {
+        report::info("Skipping the dependencies. Install them later with flutter pub add.");
     }
 }
 
-pub fn create_project_structure() {
-    if !Path::new(".env").exists() {
-        // create .env file in root
-        create_file("", ".env", None);
+/// Title of the app derived from its package name, e.g. `my_app` -> `My App`.
+fn app_title_from_package(package_name: &str) -> String {
+    package_name.split('_').filter(|word| !word.is_empty()).map(|word| word.to_string().capitalize()).collect::<Vec<String>>().join(" ")
+}
+
+/// Sets the name of the package in pubspec.yaml.
+fn rename_package(package_name: &str) {
+    let content = std::fs::read_to_string("pubspec.yaml").unwrap_or_default();
+    let renamed: Vec<String> = content
+        .lines()
+        .map(|line| if line.starts_with("name:") { format!("name: {}", package_name) } else { line.to_string() })
+        .collect();
+
+    report::info(&format!("Renaming the package to {}", package_name));
+    write_lines("pubspec.yaml", &renamed.iter().map(String::as_str).collect::<Vec<&str>>(), content.ends_with('\n'));
+}
+
+/// Files `rudder create` generates for a config and a preset, the ones only written when missing left out.
+fn project_file_paths(config: &ProjectConfig, preset: &presets::Preset) -> Vec<String> {
+    let mut paths = vec!["lib\\main.dart", "lib\\src\\app.dart", "lib\\src\\constants\\app_colors.dart"];
+    if preset.has(presets::Part::Constants) {
+        paths.extend(["lib\\src\\constants\\app_sizes.dart", "lib\\src\\constants\\app_text_styles.dart"]);
+    }
+    paths.extend([
+        "lib\\src\\exceptions\\app_exceptions.dart",
+        "lib\\src\\exceptions\\async_errors.dart",
+        "lib\\src\\routes\\routes.dart",
+        "lib\\src\\utils\\theme.dart",
+        "lib\\src\\services\\logger_service.dart",
+    ]);
+    if preset.has(presets::Part::Utils) {
+        paths.extend(["lib\\src\\utils\\extensions.dart", "lib\\src\\utils\\helper.dart"]);
+    }
+    if preset.has(presets::Part::Flavors) && !preset.flavors.is_empty() {
+        paths.push("lib\\src\\constants\\flavor.dart");
     }
 
-    if Path::new("lib/src").exists() {
-        println!("**lib/src folder already exists. Please delete the folder and try again.**");
-        return;
+    let mut paths: Vec<String> = paths.into_iter().map(str::to_string).collect();
+    paths.extend(preset.files.iter().map(|file| file.path.replace('/', "\\")));
+
+    if let Ok(blueprint) = blueprints::find(blueprints::DEFAULT_BLUEPRINT) {
+        for feature in &config.features {
+            paths.extend(feature_file_paths(&blueprint, &feature_path(feature, None), feature, preset.has(presets::Part::Tests)));
+        }
     }
 
-    // delete main.dart file
-    if Path::new("lib/main.dart").exists() {
-        std::fs::remove_file("lib/main.dart").expect("Unable to delete main.dart file");
+    paths
+}
+
+/// Generates the files of the project that don't exist yet. `main_policy` applies to an existing main.dart,
+/// which has to be replaced for the app to start from the generated structure.
+fn create_project_structure(config: &ProjectConfig, preset: &presets::Preset, main_policy: ConflictPolicy) {
+    if !Path::new(".env").exists() {
+        // create .env file in root
+        generate_file("", ".env", "empty", json!({}));
     }
 
     let root = "lib";
@@ -86,7 +2037,10 @@ pub fn create_project_structure() {
 
     // * MAIN.DART
     // create main.dart file
-    create_file(root, "main.dart", Some(main_dart()));
+    let policy = conflict_policy();
+    set_conflict_policy(main_policy);
+    generate_file(root, "main.dart", "main", json!({}));
+    set_conflict_policy(policy);
 
     // * SRC
     // create src folder
@@ -94,120 +2048,550 @@ pub fn create_project_structure() {
 
     // * APP.DART
     // create app.dart file
-    create_file(&src, "app.dart", Some(app_dart()));
+    let app_title = config.app_title.as_deref().unwrap_or("Flutter Demo");
+    let app = if config.uses_go_router() { "go_router_app" } else { "app" };
+    generate_file(&src, "app.dart", app, json!({ "app_title": app_title }));
 
     // * SHARED WIDGETS
     // create shared_widgets folder
-    create_folder(format!("{}\\shared_widgets", &src).as_str());
+    if preset.has(presets::Part::SharedWidgets) {
+        create_folder(format!("{}\\shared_widgets", &src).as_str());
+    }
 
     // * CONSTANTS
     // create constants folder
     create_folder(format!("{}\\constants", &src).as_str());
     // create app_sizes.dart
-    create_file(format!("{}\\constants", &src).as_str(), "app_sizes.dart", Some(app_sizes()));
+    if preset.has(presets::Part::Constants) {
+        generate_file(format!("{}\\constants", &src).as_str(), "app_sizes.dart", "app_sizes", json!({}));
+    }
     // create app_colors.dart
-    create_file(format!("{}\\constants", &src).as_str(), "app_colors.dart", Some(app_colors()));
+    let (seed, theme_mode) = match theme_settings(None, None) {
+        Ok(settings) => settings,
+        Err(e) => {
+            report::warning(Code::InvalidInput, &format!("{} Using the default theme.", e));
+            (theme::parse_hex_color(theme::DEFAULT_SEED).expect("Invalid default seed"), "system".to_string())
+        }
+    };
+    generate_file(format!("{}\\constants", &src).as_str(), "app_colors.dart", "app_colors", json!({ "seed": seed.to_hex() }));
     // create app_text_styles.dart
-    create_file(format!("{}\\constants", &src).as_str(), "app_text_styles.dart", Some(app_text_styles()));
+    if preset.has(presets::Part::Constants) {
+        generate_file(format!("{}\\constants", &src).as_str(), "app_text_styles.dart", "app_text_styles", json!({}));
+    }
 
     // * EXCEPTIONS
     // create exceptions folder
     create_folder(format!("{}\\exceptions", &src).as_str());
     // create app_exceptions.dart
-    create_file(format!("{}\\exceptions", &src).as_str(), "app_exceptions.dart", Some(app_exceptions()));
+    generate_file(format!("{}\\exceptions", &src).as_str(), "app_exceptions.dart", "app_exceptions", json!({}));
     // create async_errors.dart
-    create_file(format!("{}\\exceptions", &src).as_str(), "async_errors.dart", Some(async_errors()));
+    generate_file(format!("{}\\exceptions", &src).as_str(), "async_errors.dart", "async_errors", json!({}));
 
     // * ROUTES
     // create routes folder
     create_folder(format!("{}\\routes", &src).as_str());
     // create routes.dart file
-    create_file(format!("{}\\routes", &src).as_str(), "routes.dart", Some(routes_dart()));
+    let (routes, variables) = match config.uses_go_router() {
+        true => ("go_router_routes", json!({ "initial_location": format!("/{}", config.features.first().map_or("", String::as_str)) })),
+        false => ("routes", json!({})),
+    };
+    generate_file(format!("{}\\routes", &src).as_str(), "routes.dart", routes, variables);
 
     // * UTILS
     // create utils folder
     create_folder(format!("{}\\utils", &src).as_str());
     // create theme.dart file
-    create_file(format!("{}\\utils", &src).as_str(), "theme.dart", None);
-    // create an extension.dart file
-    create_file(format!("{}\\utils", &src).as_str(), "extensions.dart", Some(extensions_dart()));
-    // create helper.dart file
-    create_file(format!("{}\\utils", &src).as_str(), "helper.dart", Some(helper_dart()));
+    generate_file(format!("{}\\utils", &src).as_str(), "theme.dart", "theme", json!({ "mode": theme_mode }));
+    if preset.has(presets::Part::Utils) {
+        // create an extension.dart file
+        generate_file(format!("{}\\utils", &src).as_str(), "extensions.dart", "extensions", json!({}));
+        // create helper.dart file
+        generate_file(format!("{}\\utils", &src).as_str(), "helper.dart", "helper", json!({}));
+    }
     
 
     // * SERVICES
     // create services folder
     create_folder(format!("{}\\services", &src).as_str());
     // create logger_service.dart file
-    create_file(
+    generate_file(
         format!("{}\\services", &src).as_str(),
         "logger_service.dart",
-        Some(logger_service()),
+        "logger_service",
+        json!({}),
     );
 
     // * FEATURES
     // create features folder
     create_folder(format!("{}\\features", &src).as_str());
 
-    // add home feature
-    add_feature("home", None);
+    // add the initial features
+    if !config.features.is_empty() {
+        match blueprints::find(blueprints::DEFAULT_BLUEPRINT) {
+            Ok(blueprint) => {
+                for feature in &config.features {
+                    add_feature(feature, None, None, preset.has(presets::Part::Tests), &blueprint);
+                }
+            }
+            Err(e) => report::error(Code::InvalidInput, &e),
+        }
+    }
+
+}
+
+/// Renders the files of a custom preset from the templates in its folder.
+fn add_preset_files(config: &ProjectConfig, preset: &presets::Preset) {
+    for file in &preset.files {
+        let source = preset.template_path(&file.template);
+        if !Path::new(&source).exists() {
+            report::warning(Code::NotFound, &format!("Template {} of the {} preset doesn't exist. Skipping {}.", source, preset.name, file.path));
+            continue;
+        }
+
+        let path = file.path.replace('/', "\\");
+        let (folder, name) = path.rsplit_once('\\').unwrap_or(("", &path));
+        if !folder.is_empty() {
+            create_folder_all(folder);
+        }
+
+        let variables = json!({
+            "source": source,
+            "app_title": config.app_title.as_deref().unwrap_or_default(),
+            "package_name": config.package_name.as_deref().unwrap_or_default(),
+        });
+        generate_file(folder, name, templates::FILE_TEMPLATE, variables);
+    }
+}
 
+/// Prints the presets of `rudder create --preset`, the project's own ones included.
+pub fn list_presets() {
+    for name in presets::names() {
+        match presets::find(&name) {
+            Ok(preset) => {
+                let kind = if preset.source.is_some() { "custom" } else { "built-in" };
+                report::info(&format!("{:<12} {} ({})", name, preset.description, kind));
+            }
+            Err(e) => report::warning(Code::InvalidInput, &e),
+        }
+    }
 }
 
 pub fn create_folder(path: &str) {
-    create_dir(&path).expect(format!("Error creating folder: {}", &path).as_str());
+    if Path::new(path).is_dir() {
+        return;
+    }
+
+    match create_dir(path) {
+        Ok(()) => report::folder(path),
+        Err(e) => report::error(Code::Io, &format!("Unable to create the folder {}: {}", path, e)),
+    }
 }
 
-pub fn create_file<'a>(path: &'a str, name: &'a str, data: Option<String>) {
-    
-    let merged = if path == "" { format!("{}", &name) } else { format!("{}\\{}", &path, &name) };
+/// Creates a folder and any missing parent folders.
+pub fn create_folder_all(path: &str) {
+    let mut current = String::new();
+
+    for part in path.split('\\') {
+        current = if current.is_empty() { part.to_string() } else { format!("{}\\{}", current, part) };
+
+        if !Path::new(&current).exists() {
+            create_folder(&current);
+        }
+    }
+}
+
+/// What happens when a file rudder generates already exists with other content.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    /// Keep the existing file.
+    Skip,
+    Overwrite,
+    /// Overwrite the file after copying it to `{file}.orig`.
+    Backup,
+    /// Stop before anything is overwritten.
+    Fail,
+}
+
+/// The policy given with `--on-conflict`, `None` leaving it to the command.
+static CONFLICT_POLICY: Mutex<Option<ConflictPolicy>> = Mutex::new(None);
+
+/// Set by `rudder create`, which keeps the files other commands regenerated since it ran.
+static KEEP_REGENERATED_FILES: AtomicBool = AtomicBool::new(false);
+
+pub fn set_conflict_policy(policy: ConflictPolicy) {
+    *CONFLICT_POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(policy);
+}
+
+fn conflict_policy() -> ConflictPolicy {
+    CONFLICT_POLICY.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).unwrap_or(ConflictPolicy::Fail)
+}
+
+/// Checks the files a command is about to generate before it writes anything, so that the `fail` policy
+/// leaves the project as it was. Returns false when one of them was edited or wasn't generated by rudder.
+fn check_conflicts(paths: &[String]) -> bool {
+    if conflict_policy() != ConflictPolicy::Fail {
+        return true;
+    }
+
+    let manifest = Manifest::load().unwrap_or_default();
+    let conflicts: Vec<&String> = paths.iter().filter(|path| Path::new(path).exists() && manifest.file_state(path) != FileState::Untouched).collect();
+
+    if conflicts.is_empty() {
+        return true;
+    }
+
+    let paths = conflicts.iter().map(|path| format!("  {}", path)).collect::<Vec<String>>().join("\n");
+    report::error(
+        Code::AlreadyExists,
+        &format!("These files already exist and would be overwritten:\n{}\nNothing was written. Rerun with --on-conflict skip, overwrite or backup.", paths),
+    );
+    false
+}
+
+/// Writes a new file. An existing file is only replaced without asking when it still has the content
+/// rudder last wrote to it, otherwise the conflict policy decides. Returns false when nothing was written.
+pub fn create_file<'a>(path: &'a str, name: &'a str, data: Option<String>) -> bool {
     
-    println!("Adding {:}", merged);
+    let merged = if path.is_empty() { name.to_string() } else { format!("{}\\{}", &path, &name) };
 
-    let mut file = match File::create(&merged) {
-        Ok(file) => file,
-        Err(e) => {
-            println!("Error creating file {name}: {}", e);
+    // files edited since rudder wrote them (or not written by rudder at all) are conflicts
+    let untouched = Manifest::load().is_ok_and(|manifest| manifest.file_state(&merged) == FileState::Untouched);
+
+    write_new_file(&merged, data, untouched)
+}
+
+/// Writes a file, an existing one that isn't `untouched` going through the conflict policy.
+fn write_new_file(path: &str, data: Option<String>, untouched: bool) -> bool {
+    let existing = std::fs::read_to_string(path).ok();
+    let existed = existing.is_some() || Path::new(path).exists();
+
+    if let Some(existing) = &existing {
+        if *existing == data.as_deref().unwrap_or_default() {
+            report::detail(&format!("{} is up to date", path));
+            return true;
+        }
+    }
+
+    if existed && !untouched {
+        match conflict_policy() {
+            ConflictPolicy::Skip => {
+                report::file(path, FileStatus::Skipped);
+                return false;
+            }
+            ConflictPolicy::Overwrite => (),
+            ConflictPolicy::Backup => {
+                let backup = backup_path(path);
+                if let Err(e) = std::fs::copy(path, &backup) {
+                    report::error(Code::Io, &format!("Unable to back up {} to {}: {}", path, backup, e));
+                    return false;
+                }
+                report::backup(path, &backup);
+            }
+            ConflictPolicy::Fail => {
+                report::error(Code::AlreadyExists, &format!("{} already exists and would be overwritten. Rerun with --on-conflict skip, overwrite or backup.", path));
+                return false;
+            }
+        }
+    }
+
+    let written = File::create(path).and_then(|mut file| file.write_all(data.unwrap_or_default().as_bytes()));
+    if let Err(e) = written {
+        report::error(Code::Io, &format!("Unable to write {}: {}", path, e));
+        return false;
+    }
+
+    report::file(path, if existed { FileStatus::Overwritten } else { FileStatus::Created });
+
+    true
+}
+
+/// `{path}.orig`, or `{path}.orig.2`, `{path}.orig.3`... when older backups exist.
+fn backup_path(path: &str) -> String {
+    let mut backup = format!("{}.orig", path);
+    let mut index = 2;
+
+    while Path::new(&backup).exists() {
+        backup = format!("{}.orig.{}", path, index);
+        index += 1;
+    }
+
+    backup
+}
+
+/// Renders a template of the registry into a file and records it in the manifest.
+fn generate_file(path: &str, name: &str, template: &str, variables: serde_json::Value) {
+    let variables = variables.as_object().cloned().unwrap_or_default();
+    let content = match templates::render(template, &variables) {
+        Some(content) => content,
+        None => {
+            report::error(Code::InvalidInput, &format!("Unable to render {}\\{} from the template {}.", path, name, template));
+            return;
+        }
+    };
+    create_generated_file(path, name, template, serde_json::Value::Object(variables), content);
+}
+
+/// Writes a file generated from other project files (or from a template) and records it in the manifest.
+fn create_generated_file(path: &str, name: &str, template: &str, variables: serde_json::Value, content: String) {
+    let merged = if path.is_empty() { name.to_string() } else { format!("{}\\{}", &path, &name) };
+
+    // while creating the project, a file recorded with another template or other variables was regenerated
+    // by another command since (e.g. app_colors.dart by rudder tokens import) and is a conflict too
+    let untouched = Manifest::load().is_ok_and(|manifest| {
+        let regenerated = manifest.files.get(&merged).is_some_and(|entry| entry.template != template || variables.as_object() != Some(&entry.variables));
+        manifest.file_state(&merged) == FileState::Untouched && !(regenerated && KEEP_REGENERATED_FILES.load(Ordering::Relaxed))
+    });
+
+    if write_new_file(&merged, Some(content.clone()), untouched) {
+        record_generated_file(&merged, template, variables, &content);
+    }
+}
+
+/// Replaces a generated file with another template, e.g. a screen switched over to the ARB files.
+fn rewrite_generated_file(path: &str, template: &str, variables: serde_json::Value) {
+    let variables = variables.as_object().cloned().unwrap_or_default();
+    let content = match templates::render(template, &variables) {
+        Some(content) => content,
+        None => {
+            report::error(Code::InvalidInput, &format!("Unable to render {} from the template {}.", path, template));
             return;
-        },
+        }
     };
-        
+    if write_file(path, &content) {
+        record_generated_file(path, template, serde_json::Value::Object(variables), &content);
+    }
+}
 
-    if let Some(data) = data {
-        // write to file
-        file.write_all(data.as_bytes()).expect("Error writing to file.");
+fn record_generated_file(path: &str, template: &str, variables: serde_json::Value, content: &str) {
+    let version = templates::find(template).map_or(1, |template| template.version);
+    if let Err(e) = manifest::record(path, template, version, variables.as_object().cloned().unwrap_or_default(), content) {
+        report::warning(Code::Io, &e);
     }
 }
 
-pub fn add_dependencies() {
+/// Writes a whole file, reporting it as updated, or created when it didn't exist.
+/// A generated file that was untouched stays untouched, its edits being rudder's own. Returns false when the write failed.
+fn write_file(path: &str, content: impl AsRef<[u8]>) -> bool {
+    let existed = Path::new(path).exists();
+    let previous = std::fs::read_to_string(path).unwrap_or_default();
+    if let Err(e) = std::fs::write(path, content.as_ref()) {
+        report::error(Code::Io, &format!("Unable to write {}: {}", path, e));
+        return false;
+    }
+    report::file(path, if existed { FileStatus::Updated } else { FileStatus::Created });
+
+    if let Err(e) = manifest::update_hash(path, &previous, &String::from_utf8_lossy(content.as_ref())) {
+        report::warning(Code::Io, &e);
+    }
 
-    let dependencies = format!("flutter pub add {}", ["auto_route", "flutter_riverpod", "logger", "responsive_framework", "intl", "flutter_dotenv"].join(" "));
-    let dev_dependencies = format!("flutter pub add --dev {}", ["build_runner", "auto_route_generator", "riverpod_lint"].join(" "));
+    true
+}
 
-     if cfg!(target_os = "windows") {
-        println!("\nInstalling dependencies...");
-        Command::new("cmd")
-                .args(["/C", &dependencies]).output().expect("Failed to install dependencies");
-        println!("Dependencies installed successfully!");
+pub fn add_dependencies(config: &ProjectConfig) {
+    let (router, router_generator) = if config.uses_go_router() { (&["go_router"][..], &[][..]) } else { (&["auto_route"][..], &["auto_route_generator"][..]) };
 
-        println!("\nInstalling dev dependencies...");
-        Command::new("cmd")
-                .args(["/C", &dev_dependencies]).output().expect("Failed to install dev dependencies");   
-        println!("Dev dependencies installed successfully!");   
+    // packages already in pubspec.yaml are left as they are when create runs again
+    ensure_dependencies(
+        &[router, &["flutter_riverpod", "logger", "responsive_framework", "intl", "flutter_dotenv", FREEZED_ANNOTATION]].concat(),
+        &[router_generator, &["build_runner", "riverpod_lint", FREEZED]].concat(),
+    );
+}
+
+/// Appends a route to the end of the list opened by the line ending with `list_start`, e.g. `routes: [`,
+/// keeping the routes in the order they were added.
+fn append_route(routes_path: &str, list_start: &str, route: &str) -> bool {
+    let content = std::fs::read_to_string(routes_path).unwrap_or_default();
+    let lines: Vec<&str> = content.lines().collect();
+
+    let start = match lines.iter().position(|line| line.trim_end().ends_with(list_start)) {
+        Some(start) => start,
+        None => return false,
+    };
+
+    // find the line closing the list
+    let mut depth = 0;
+    let end = lines.iter().enumerate().skip(start).find_map(|(index, line)| {
+        for c in line.chars() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => (),
+            }
+        }
+        (depth == 0).then_some(index)
+    });
+
+    let end = match end {
+        Some(end) if end > start => end,
+        _ => return false,
+    };
+
+    if lines[start..end].iter().any(|line| line.trim() == route) {
+        return true;
+    }
+
+    let line = format!("{}    {}", leading_whitespace(lines[end]), route);
+    let mut lines = lines;
+    lines.insert(end, &line);
+    write_lines(routes_path, &lines, content.ends_with('\n'));
+    true
+}
+
+/// Route path of a feature and its folder under lib/src. Sub features are nested under their feature's path.
+fn feature_route(feature_name: &str, sub_feature_path: Option<&str>) -> (String, String) {
+    // lib\src\features\orders\sub_features\checkout -> features/orders/sub_features/checkout
+    match sub_feature_path {
+        Some(sub_feature_path) => {
+            let folder = sub_feature_path.trim_start_matches("lib\\src\\").replace('\\', "/");
+            let parent = folder.split('/').nth(1).unwrap_or_default().to_string();
+            (format!("/{}/{}", parent, feature_name), folder)
+        }
+        None => (format!("/{}", feature_name), format!("features/{}", feature_name)),
+    }
+}
+
+/// Adds a GoRoute for a screen of a feature to routes.dart, under the feature's path. The parameters
+/// of the path, e.g. `:id`, are given to the screen.
+fn register_go_route(feature_name: &str, sub_feature_path: Option<&str>, screen: &str, route_path: &str) {
+    let routes_path = "lib\\src\\routes\\routes.dart";
+    let class_name = screen.to_string().to_pascal_case().capitalize();
+
+    let (feature_path, folder) = feature_route(feature_name, sub_feature_path);
+    let path = if route_path.is_empty() { feature_path } else { format!("{}/{}", feature_path, route_path) };
+
+    let parameters: Vec<String> = route_path
+        .split('/')
+        .filter_map(|segment| segment.strip_prefix(':'))
+        .map(|parameter| format!("{}: state.pathParameters['{}']!", camel_case(parameter), parameter))
+        .collect();
+    let builder = if parameters.is_empty() { format!("const {}Screen()", class_name) } else { format!("{}Screen({})", class_name, parameters.join(", ")) };
+
+    let route = format!("GoRoute(path: '{}', builder: (context, state) => {}),", path, builder);
+
+    let wired = add_import(routes_path, &format!("import '../{}/presentation/{}_screen.dart';", folder, screen)) && append_route(routes_path, "routes: [", &route);
+
+    if !wired {
+        report::warning(Code::ManualStep, &format!("Unable to register {} in {}. Add {} to the routes manually.", path, routes_path, route));
+    }
+}
+
+/// Adds an AutoRoute for a screen of a feature to the routes of AppRouter, under the feature's path.
+/// The page is generated from the `@RoutePage` of the screen, and the parameters of the path are read with `@PathParam`.
+fn register_auto_route(feature_name: &str, sub_feature_path: Option<&str>, screen: &str, route_path: &str) {
+    let routes_path = "lib\\src\\routes\\routes.dart";
+    let class_name = screen.to_string().to_pascal_case().capitalize();
+
+    let (feature_path, _) = feature_route(feature_name, sub_feature_path);
+    let path = if route_path.is_empty() { feature_path } else { format!("{}/{}", feature_path, route_path) };
+
+    // routes are named after their screen, e.g. OrdersDetailScreen -> OrdersDetailRoute
+    let route = format!("AutoRoute(path: '{}', page: {}Route.page),", path, class_name);
+
+    if !append_route(routes_path, "get routes => [", &route) {
+        report::warning(Code::ManualStep, &format!("Unable to register {} in {}. Add {} to the routes of AppRouter manually.", path, routes_path, route));
+    }
+}
+
+/// freezed 3 no longer generates the `when` of the unions, e.g. the one AppException's messages are read with.
+const FREEZED: &str = "freezed:^2.5.7";
+const FREEZED_ANNOTATION: &str = "freezed_annotation:^2.4.4";
+
+/// Adds only the packages that are not yet declared in pubspec.yaml.
+pub fn ensure_dependencies(dependencies: &[&str], dev_dependencies: &[&str]) {
+    let install = ProjectConfig::load().map_or(true, |config| config.install_dependencies);
+    let pubspec = std::fs::read_to_string("pubspec.yaml").unwrap_or_default();
+    let declared: Vec<&str> = pubspec
+        .lines()
+        .filter_map(|line| line.trim().split_once(':').map(|(name, _)| name.trim()))
+        .collect();
+
+    // a package can come with its version constraint, e.g. `freezed:^2.5.7`
+    let missing = |packages: &[&str]| -> Vec<String> {
+        packages.iter().filter(|package| !declared.contains(&package.split(':').next().unwrap_or_default())).map(|package| package.to_string()).collect()
+    };
+
+    let dependencies = missing(dependencies);
+    let dev_dependencies = missing(dev_dependencies);
+
+    // the project opted out of installing packages in rudder create
+    if !install {
+        if !dependencies.is_empty() || !dev_dependencies.is_empty() {
+            report::info(&format!("Not installing {}. Add them with flutter pub add.", [dependencies, dev_dependencies].concat().join(", ")));
+        }
+        return;
+    }
+
+    if !dependencies.is_empty() {
+        pub_add(&dependencies.iter().map(String::as_str).collect::<Vec<&str>>(), false);
+    }
+
+    if !dev_dependencies.is_empty() {
+        pub_add(&dev_dependencies.iter().map(String::as_str).collect::<Vec<&str>>(), true);
+    }
+}
+
+fn pub_add(packages: &[&str], dev: bool) {
+    // quoted for the `^` of the version constraints, an escape character of cmd
+    let arguments = packages.iter().map(|package| format!("\"{}\"", package)).collect::<Vec<String>>().join(" ");
+    let (command, label) = if dev {
+        (format!("flutter pub add --dev {}", arguments), "dev dependencies")
     } else {
+        (format!("flutter pub add {}", arguments), "dependencies")
+    };
 
-        println!("\nInstalling dependencies...");
-        Command::new("sh")
-                .arg("-c")
-                .arg(&dependencies)
-                .output().expect("Failed to install dependencies");
-        println!("Dependencies installed successfully!");
+    report::info(&format!("\nInstalling {}...", label));
+    report::detail(&command);
 
-        println!("\nInstalling dev dependencies...");
+    let output = if cfg!(target_os = "windows") {
+        Command::new("cmd")
+                .args(["/C", &command]).output()
+    } else {
         Command::new("sh")
                 .arg("-c")
-                .arg(&dev_dependencies)
-                .output().expect("Failed to install dev dependencies");
-        println!("Dev dependencies installed successfully!");
+                .arg(&command)
+                .output()
+    };
+
+    let result = match output {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+        Err(e) => Err(e.to_string()),
     };
-}
\ No newline at end of file
+
+    report::dependencies(packages, dev, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "class Home {\n  final int a;\n\n  final int b;\n}";
+
+    #[test]
+    fn three_way_merge_applies_both_sides() {
+        let ours = "class Home {\n  final int a = 1;\n\n  final int b;\n}";
+        let theirs = "class Home {\n  final int a;\n\n  final int b;\n\n  final int c;\n}";
+
+        let merged = three_way_merge(BASE, ours, theirs).unwrap();
+        assert_eq!(merged, "class Home {\n  final int a = 1;\n\n  final int b;\n\n  final int c;\n}");
+    }
+
+    #[test]
+    fn three_way_merge_keeps_the_trailing_newline_of_ours() {
+        let ours = format!("{}\n", BASE);
+        assert_eq!(three_way_merge(BASE, &ours, BASE).unwrap(), ours);
+        assert_eq!(three_way_merge(BASE, BASE, BASE).unwrap(), BASE);
+    }
+
+    #[test]
+    fn three_way_merge_reports_conflicts_with_markers() {
+        let ours = "class Home {\n  final int a = 1;\n\n  final int b;\n}";
+        let theirs = "class Home {\n  final int a = 2;\n\n  final int b;\n}";
+
+        let conflicted = three_way_merge(BASE, ours, theirs).unwrap_err();
+        assert_eq!(
+            conflicted,
+            "class Home {\n<<<<<<< current\n  final int a = 1;\n=======\n  final int a = 2;\n>>>>>>> upgraded\n\n  final int b;\n}\n"
+        );
+    }
+}
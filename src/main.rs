@@ -1,40 +1,163 @@
 mod args;
-mod dart;
+mod completions;
 
-use args::{RudderArgs, RudderCommand};
+use args::{AddSubcommand, EnvCommand, FlavorsCommand, ImportCommand, L10nCommand, PresetsCommand, TokensCommand, RudderArgs, RudderCommand};
 use clap::Parser;
-use rudder::{create_project_structure, add_dependencies, add_feature};
+use rudder::config::ProjectConfig;
+use rudder::blueprints;
+use rudder::report::{self, Code, Format, Verbosity};
+use rudder::{create_project, add_feature, add_model, add_model_from_json, add_exception, add_method, add_service, add_widget, import_openapi, add_env, sync_env, add_flavors, init_l10n, generate_theme, import_tokens, list_presets, upgrade, diff, complete, set_conflict_policy, split_list, ConflictPolicy};
 
 fn main() {
     let args: RudderArgs = RudderArgs::parse();
 
-    match args.command {
-        RudderCommand::Create => {
-            println!("Creating project structure...");
-            create_project_structure();
-            println!("Project structure created successfully!");
-            add_dependencies();
+    let format = if args.output == "json" { Format::Json } else { Format::Human };
+    let verbosity = match (args.quiet, args.verbose) {
+        (true, _) => Verbosity::Quiet,
+        (_, true) => Verbosity::Verbose,
+        _ => Verbosity::Normal,
+    };
+    report::configure(format, verbosity);
+
+    if let Some(on_conflict) = args.on_conflict.as_deref() {
+        set_conflict_policy(match on_conflict {
+            "skip" => ConflictPolicy::Skip,
+            "overwrite" => ConflictPolicy::Overwrite,
+            "backup" => ConflictPolicy::Backup,
+            _ => ConflictPolicy::Fail,
+        });
+    }
+
+    run(args.command);
+
+    std::process::exit(report::finish());
+}
+
+fn run(command: RudderCommand) {
+    match command {
+        RudderCommand::Create(create_command) => {
+            let defaults = ProjectConfig::default();
+
+            let answers = ProjectConfig {
+                preset: create_command.preset,
+                app_title: create_command.app_title,
+                package_name: create_command.package_name,
+                state_management: create_command.state_management.unwrap_or(defaults.state_management),
+                router: create_command.router.unwrap_or(defaults.router),
+                services: create_command.services.as_deref().map(split_list).unwrap_or_default(),
+                features: split_list(&create_command.features),
+                install_dependencies: !create_command.no_install,
+                ..defaults
+            };
+
+            create_project(create_command.interactive, answers);
         }
         RudderCommand::Add(add_command) => {
-            
+            match add_command.command {
+                Some(AddSubcommand::Model(model_command)) => {
+                    let sub_feature = model_command.sub_feature.as_deref();
+                    match (model_command.from_json, model_command.fields) {
+                        (Some(json_path), _) => add_model_from_json(&model_command.feature, sub_feature, model_command.name.as_deref(), &json_path),
+                        (None, Some(fields)) => add_model(&model_command.feature, sub_feature, &model_command.name.unwrap_or_default(), &fields),
+                        (None, None) => (),
+                    }
+                    return;
+                }
+                Some(AddSubcommand::Method(method_command)) => {
+                    add_method(
+                        &method_command.feature,
+                        method_command.sub_feature.as_deref(),
+                        &method_command.name,
+                        &method_command.returns,
+                        method_command.params.as_deref(),
+                        &method_command.repository,
+                    );
+                    return;
+                }
+                Some(AddSubcommand::Service(service_command)) => {
+                    add_service(&service_command.name);
+                    return;
+                }
+                Some(AddSubcommand::Widget(widget_command)) => {
+                    add_widget(&widget_command.name, widget_command.feature.as_deref(), widget_command.sub_feature.as_deref(), &widget_command.kind);
+                    return;
+                }
+                Some(AddSubcommand::Exception(exception_command)) => {
+                    add_exception(&exception_command.feature, exception_command.sub_feature.as_deref(), &exception_command.name, &exception_command.message);
+                    return;
+                }
+                None => (),
+            }
+
+            let features = add_command.features.unwrap_or_default();
+
+            // an unknown blueprint is reported once, before any feature is added
+            let blueprint = match blueprints::find(add_command.blueprint.as_deref().unwrap_or(blueprints::DEFAULT_BLUEPRINT)) {
+                Ok(blueprint) => blueprint,
+                Err(e) => {
+                    report::error(Code::InvalidArgument, &e);
+                    return;
+                }
+            };
 
             // Add a sub feature to existing parent feature.
             if let Some (sub_feature) = add_command.sub_feature {
-                for feature in add_command.features.split(",") {
-                    println!("Adding sub feature {} to {}...", sub_feature, feature);
-                    add_feature(feature.trim(), Some(sub_feature.as_str()));
-                    println!("Sub feature {} added successfully!", sub_feature);
+                for feature in features.split(",") {
+                    report::info(&format!("Adding sub feature {} to {}...", sub_feature, feature));
+                    if !add_feature(feature.trim(), Some(sub_feature.as_str()), add_command.states.as_deref(), !add_command.no_tests, &blueprint) {
+                        return;
+                    }
+                    report::info(&format!("Sub feature {} added successfully!", sub_feature));
                 }
                 return;
             }
 
             // Add a feature.
-            for feature in add_command.features.split(",") {
-                println!("Adding {} feature...", feature);
-                add_feature(feature.trim(), None);
-                println!("{} feature added successfully!\n", feature);
+            for feature in features.split(",") {
+                report::info(&format!("Adding {} feature...", feature));
+                if !add_feature(feature.trim(), None, add_command.states.as_deref(), !add_command.no_tests, &blueprint) {
+                    return;
+                }
+                report::info(&format!("{} feature added successfully!\n", feature));
             }
         }
+        RudderCommand::Import(ImportCommand::Openapi(openapi_command)) => {
+            report::info(&format!("Importing {}...", openapi_command.spec));
+            import_openapi(&openapi_command.spec, &openapi_command.feature, openapi_command.tag.as_deref());
+        }
+        RudderCommand::Env(EnvCommand::Add(env_command)) => {
+            add_env(&env_command.key, env_command.r#type.as_deref(), env_command.default.as_deref());
+        }
+        RudderCommand::Env(EnvCommand::Sync) => {
+            sync_env();
+        }
+        RudderCommand::Flavors(FlavorsCommand::Add(flavors_command)) => {
+            add_flavors(&flavors_command.flavors);
+        }
+        RudderCommand::L10n(L10nCommand::Init(l10n_command)) => {
+            init_l10n(&l10n_command.locales);
+        }
+        RudderCommand::Theme(theme_command) => {
+            generate_theme(theme_command.seed.as_deref(), theme_command.mode.as_deref());
+        }
+        RudderCommand::Tokens(TokensCommand::Import(tokens_command)) => {
+            import_tokens(&tokens_command.file);
+        }
+        RudderCommand::Presets(PresetsCommand::List) => {
+            list_presets();
+        }
+        RudderCommand::Upgrade => {
+            upgrade();
+        }
+        RudderCommand::Diff(diff_command) => {
+            diff(diff_command.target.as_deref());
+        }
+        RudderCommand::Completions(completions_command) => {
+            completions::print_completions(&completions_command.shell);
+        }
+        RudderCommand::Complete(complete_command) => {
+            complete(&complete_command.kind, complete_command.line.as_deref());
+        }
     }
 
 }
@@ -0,0 +1,125 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+pub const MANIFEST_PATH: &str = ".rudder\\manifest.json";
+
+/// Folder keeping the content of every generated file as it was generated, the base of `rudder upgrade`'s merges.
+pub const BASE_PATH: &str = ".rudder\\base";
+
+/// Every file rudder generated, keyed by its path, so later commands can tell untouched files from edited ones.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Manifest {
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Id of the template in the registry of `templates.rs`.
+    pub template: String,
+    /// Version of the template when the file was rendered.
+    pub version: u32,
+    /// Values the template was rendered with.
+    pub variables: Map<String, Value>,
+    /// Hash of the content as rudder last wrote it, generated or edited by another rudder command.
+    pub hash: String,
+}
+
+/// State of a file compared to what was generated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileState {
+    /// Not generated by rudder, or generated before the manifest existed.
+    Untracked,
+    /// As rudder last wrote it.
+    Untouched,
+    /// Edited by hand since rudder last wrote it.
+    Modified,
+    Missing,
+}
+
+impl Manifest {
+    /// Reads .rudder/manifest.json, which is empty until rudder generates a file.
+    pub fn load() -> Result<Manifest, String> {
+        match std::fs::read_to_string(MANIFEST_PATH) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| format!("Invalid {}: {}", MANIFEST_PATH, e)),
+            Err(_) => Ok(Manifest::default()),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(MANIFEST_PATH, format!("{}\n", content)).map_err(|e| format!("Unable to write {}: {}", MANIFEST_PATH, e))
+    }
+
+    pub fn file_state(&self, path: &str) -> FileState {
+        let entry = match self.files.get(path) {
+            Some(entry) => entry,
+            None => return FileState::Untracked,
+        };
+
+        match std::fs::read_to_string(path) {
+            Ok(content) if hash(&content) == entry.hash => FileState::Untouched,
+            Ok(_) => FileState::Modified,
+            Err(_) => FileState::Missing,
+        }
+    }
+}
+
+/// `sha256:` followed by the hex digest of the content.
+pub fn hash(content: &str) -> String {
+    let digest = Sha256::digest(content.as_bytes());
+    format!("sha256:{}", digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+}
+
+/// Records a generated file, replacing what was recorded for its path before.
+pub fn record(path: &str, template: &str, version: u32, variables: Map<String, Value>, content: &str) -> Result<(), String> {
+    let mut manifest = Manifest::load()?;
+
+    manifest.files.insert(
+        path.to_string(),
+        ManifestEntry { template: template.to_string(), version, variables, hash: hash(content) },
+    );
+
+    if !Path::new(".rudder").exists() {
+        std::fs::create_dir(".rudder").map_err(|e| format!("Unable to create .rudder: {}", e))?;
+    }
+
+    write_base(path, content)?;
+    manifest.save()
+}
+
+/// Follows an edit rudder makes to a generated file, e.g. an import added to it, so the file
+/// still counts as untouched. Files edited by hand before stay modified.
+pub fn update_hash(path: &str, previous: &str, content: &str) -> Result<(), String> {
+    let mut manifest = Manifest::load()?;
+
+    match manifest.files.get_mut(path) {
+        Some(entry) if entry.hash == hash(previous) && entry.hash != hash(content) => entry.hash = hash(content),
+        _ => return Ok(()),
+    }
+
+    manifest.save()
+}
+
+fn base_file(path: &str) -> String {
+    format!("{}\\{}", BASE_PATH, path)
+}
+
+/// Content of a file as it was last generated, `None` for files generated before the snapshots were kept.
+pub fn read_base(path: &str) -> Option<String> {
+    std::fs::read_to_string(base_file(path)).ok()
+}
+
+fn write_base(path: &str, content: &str) -> Result<(), String> {
+    let base_file = base_file(path);
+
+    if let Some(parent) = Path::new(&base_file).parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Unable to create {}: {}", parent.display(), e))?;
+    }
+
+    std::fs::write(&base_file, content).map_err(|e| format!("Unable to write {}: {}", base_file, e))
+}
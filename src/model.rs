@@ -0,0 +1,221 @@
+/// Reserved words of Dart, and the built-in identifiers that can't name a field of a freezed constructor.
+const DART_KEYWORDS: [&str; 40] = [
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+    "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new",
+    "null", "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var",
+    "void", "while", "with", "await", "yield", "late", "required", "dynamic", "covariant", "factory",
+];
+
+/// A single field of a generated Dart data class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DartField {
+    pub name: String,
+    pub dart_type: String,
+    /// Original JSON key when it differs from the Dart field name.
+    pub json_key: Option<String>,
+}
+
+impl DartField {
+    pub fn new(name: &str, dart_type: &str) -> Self {
+        DartField { name: name.to_string(), dart_type: dart_type.to_string(), json_key: None }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        self.dart_type.ends_with('?') || self.dart_type == "dynamic"
+    }
+}
+
+/// A Dart data class rendered as a freezed + json_serializable model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DartModel {
+    pub name: String,
+    pub fields: Vec<DartField>,
+}
+
+/// A case of a feature's freezed UI state union, e.g. `loaded:List<Item>`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnionState {
+    pub name: String,
+    pub payload: Option<String>,
+}
+
+impl UnionState {
+    /// Name of the payload parameter: `error` for error states, `data` for everything else.
+    pub fn payload_name(&self) -> &str {
+        if self.name == "error" || self.name == "failure" { "error" } else { "data" }
+    }
+}
+
+/// Splits on `separator`, ignoring separators nested inside `<...>` so that
+/// types such as `Map<String, int>` survive intact.
+pub fn split_top_level(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0;
+
+    for c in value.chars() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth -= 1,
+            _ => (),
+        }
+
+        if c == separator && depth == 0 {
+            parts.push(current.trim().to_string());
+            current.clear();
+        } else {
+            current.push(c);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+
+    parts
+}
+
+/// Parses a `name:Type,name:Type?` field list as passed to `--fields`.
+pub fn parse_fields(fields: &str) -> Result<Vec<DartField>, String> {
+    split_top_level(fields, ',')
+        .iter()
+        .map(|field| match field.split_once(':') {
+            Some((name, dart_type)) if !name.trim().is_empty() && !dart_type.trim().is_empty() => {
+                Ok(DartField::new(name.trim(), dart_type.trim()))
+            }
+            _ => Err(format!("Invalid field '{}'. Expected name:Type.", field)),
+        })
+        .collect()
+}
+
+fn words(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `shipping_address`, `shipping-address` and `shippingAddress` all become `ShippingAddress`.
+pub fn pascal_case(value: &str) -> String {
+    words(value)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Converts a JSON key or operation id into a valid Dart identifier.
+pub fn camel_case(key: &str) -> String {
+    let pascal = pascal_case(key);
+    let mut chars = pascal.chars();
+    let mut name = match chars.next() {
+        Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        None => "value".to_string(),
+    };
+
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name = format!("field{}", name);
+    }
+
+    if DART_KEYWORDS.contains(&name.as_str()) {
+        name = format!("{}Value", name);
+    }
+
+    name
+}
+
+/// Name of a field, numbered when another field of the model already has it, e.g. `user_id` and `userId`.
+pub fn unique_field_name(name: String, fields: &[DartField]) -> String {
+    if !fields.iter().any(|field| field.name == name) {
+        return name;
+    }
+
+    (2..).map(|index| format!("{}{}", name, index)).find(|candidate| !fields.iter().any(|field| field.name == *candidate)).expect("Unbounded range")
+}
+
+/// Parses a `loading,empty,loaded:List<Item>,error:Object` state list as passed to `--states`.
+pub fn parse_states(states: &str) -> Result<Vec<UnionState>, String> {
+    let states = split_top_level(states, ',')
+        .iter()
+        .map(|state| {
+            let (name, payload) = match state.split_once(':') {
+                Some((name, payload)) => (name.trim(), Some(payload.trim().to_string())),
+                None => (state.trim(), None),
+            };
+
+            if name.is_empty() || payload.as_deref() == Some("") {
+                return Err(format!("Invalid state '{}'. Expected name or name:Type.", state));
+            }
+
+            Ok(UnionState { name: camel_case(name), payload })
+        })
+        .collect::<Result<Vec<UnionState>, String>>()?;
+
+    if !states.iter().any(|state| state.payload.is_none()) {
+        return Err("At least one state needs to have no payload so it can be used as the initial state.".to_string());
+    }
+
+    Ok(states)
+}
+
+const DART_CORE_TYPES: [&str; 16] = [
+    "int", "double", "num", "bool", "String", "List", "Map", "Set", "Iterable", "DateTime",
+    "Duration", "Object", "dynamic", "void", "Future", "Stream",
+];
+
+/// Dart signature of an async method, e.g. `Future<List<Order>> fetchOrders(String status, {int? page})`.
+/// Nullable parameters become optional named parameters.
+pub fn method_signature(name: &str, returns: &str, params: &[DartField]) -> String {
+    let positional: Vec<String> = params.iter().filter(|param| !param.is_nullable()).map(|param| format!("{} {}", param.dart_type, param.name)).collect();
+    let named: Vec<String> = params.iter().filter(|param| param.is_nullable()).map(|param| format!("{} {}", param.dart_type, param.name)).collect();
+
+    let mut declarations = positional;
+    if !named.is_empty() {
+        declarations.push(format!("{{{}}}", named.join(", ")));
+    }
+
+    format!("Future<{}> {}({})", returns, name, declarations.join(", "))
+}
+
+/// Arguments forwarding `params` to a method with the same signature.
+pub fn method_arguments(params: &[DartField]) -> String {
+    params
+        .iter()
+        .map(|param| if param.is_nullable() { format!("{name}: {name}", name = param.name) } else { param.name.clone() })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Whether a type such as `List<Order>` refers to a class outside of dart:core.
+pub fn uses_custom_types(dart_type: &str) -> bool {
+    dart_type
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty())
+        .any(|word| !DART_CORE_TYPES.contains(&word))
+}
+
+/// A placeholder value of `dart_type` for fakes, or `None` when there is no generic one.
+pub fn dart_default_value(dart_type: &str) -> Option<String> {
+    if dart_type.ends_with('?') || dart_type == "dynamic" {
+        return Some("null".to_string());
+    }
+
+    let value = match dart_type {
+        "int" | "num" => "0",
+        "double" => "0.0",
+        "bool" => "false",
+        "String" => "''",
+        "DateTime" => "DateTime.fromMillisecondsSinceEpoch(0)",
+        _ if dart_type.starts_with("List<") => "const []",
+        _ if dart_type.starts_with("Map<") || dart_type.starts_with("Set<") => "const {}",
+        _ => return None,
+    };
+
+    Some(value.to_string())
+}
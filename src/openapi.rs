@@ -0,0 +1,576 @@
+use serde_json::{Map, Value};
+
+use crate::model::{camel_case, dart_default_value, pascal_case, unique_field_name, DartField, DartModel};
+
+const HTTP_METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParamLocation {
+    Path,
+    Query,
+}
+
+#[derive(Debug, Clone)]
+pub struct Param {
+    pub name: String,
+    /// Name of the parameter in the spec, used in the URL.
+    pub key: String,
+    pub dart_type: String,
+    pub location: ParamLocation,
+}
+
+/// A single API operation, turned into one repository method.
+#[derive(Debug, Clone)]
+pub struct Operation {
+    pub name: String,
+    pub method: String,
+    pub path: String,
+    pub params: Vec<Param>,
+    pub body: Option<String>,
+    pub returns: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiSpec {
+    pub models: Vec<DartModel>,
+    pub operations: Vec<Operation>,
+    pub base_url: Option<String>,
+}
+
+/// Parses an OpenAPI document. JSON is valid YAML, so both formats are accepted.
+pub fn parse_spec(source: &str) -> Result<Value, String> {
+    let yaml: serde_yaml::Value = serde_yaml::from_str(source).map_err(|e| e.to_string())?;
+    Ok(yaml_to_json(yaml))
+}
+
+fn yaml_to_json(value: serde_yaml::Value) -> Value {
+    match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(value) => Value::Bool(value),
+        serde_yaml::Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(value), _) => Value::from(value),
+            (None, Some(value)) => Value::from(value),
+            _ => Value::Null,
+        },
+        serde_yaml::Value::String(value) => Value::String(value),
+        serde_yaml::Value::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, value)| {
+                    // response codes such as `200:` are parsed as numbers
+                    let key = match key {
+                        serde_yaml::Value::String(key) => key,
+                        serde_yaml::Value::Number(number) => number.to_string(),
+                        serde_yaml::Value::Bool(value) => value.to_string(),
+                        _ => String::new(),
+                    };
+                    (key, yaml_to_json(value))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// Collects the operations (optionally only those tagged `tag`) and the models they need.
+/// Without a tag every object schema in `components/schemas` becomes a model.
+pub fn read_spec(spec: &Value, tag: Option<&str>) -> Result<ApiSpec, String> {
+    if spec.get("openapi").is_none() && spec.get("swagger").is_none() {
+        return Err("The file is not an OpenAPI document.".to_string());
+    }
+
+    let mut resolver = Resolver { spec, models: Vec::new(), referenced: Vec::new() };
+    let mut operations: Vec<Operation> = Vec::new();
+
+    let empty = Map::new();
+    let paths = spec.get("paths").and_then(Value::as_object).unwrap_or(&empty);
+
+    for (path, item) in paths {
+        let item = resolver.resolve(item);
+        let shared_params = item.get("parameters").and_then(Value::as_array).cloned().unwrap_or_default();
+
+        for method in HTTP_METHODS {
+            let operation = match item.get(method) {
+                Some(operation) => operation,
+                None => continue,
+            };
+
+            if let Some(tag) = tag {
+                let tagged = operation
+                    .get("tags")
+                    .and_then(Value::as_array)
+                    .is_some_and(|tags| tags.iter().any(|value| value.as_str() == Some(tag)));
+                if !tagged {
+                    continue;
+                }
+            }
+
+            let mut name = match operation.get("operationId").and_then(Value::as_str) {
+                Some(id) => camel_case(id),
+                None => camel_case(&format!("{} {}", method, path_words(path))),
+            };
+            if operations.iter().any(|other| other.name == name) {
+                name = camel_case(&format!("{} {} {}", name, method, path_words(path)));
+            }
+
+            let op_params = operation.get("parameters").and_then(Value::as_array).cloned().unwrap_or_default();
+            let mut params: Vec<Param> = Vec::new();
+
+            for param in shared_params.iter().chain(op_params.iter()) {
+                let param = resolver.resolve(param);
+                let key = param.get("name").and_then(Value::as_str).unwrap_or_default().to_string();
+                let location = match param.get("in").and_then(Value::as_str) {
+                    Some("path") => ParamLocation::Path,
+                    Some("query") => ParamLocation::Query,
+                    _ => continue,
+                };
+                let required = location == ParamLocation::Path || param.get("required").and_then(Value::as_bool).unwrap_or(false);
+                let hint = format!("{}{}", pascal_case(&name), pascal_case(&key));
+                let mut dart_type = resolver.schema_type(param.get("schema").unwrap_or(&Value::Null), &hint);
+                if !required {
+                    dart_type = nullable(&dart_type);
+                }
+
+                // operation level parameters override path level ones
+                params.retain(|other| other.key != key);
+                params.push(Param { name: camel_case(&key), key, dart_type, location });
+            }
+
+            let body = operation
+                .get("requestBody")
+                .map(|body| resolver.resolve(body))
+                .and_then(json_schema)
+                .map(|schema| resolver.schema_type(schema, &format!("{}Request", pascal_case(&name))));
+
+            let returns = operation
+                .get("responses")
+                .and_then(Value::as_object)
+                .and_then(|responses| responses.iter().find(|(code, _)| code.starts_with('2')))
+                .map(|(_, response)| resolver.resolve(response))
+                .and_then(json_schema)
+                .map(|schema| resolver.schema_type(schema, &format!("{}Response", pascal_case(&name))))
+                .unwrap_or_else(|| "void".to_string());
+
+            operations.push(Operation { name, method: method.to_string(), path: path.clone(), params, body, returns });
+        }
+    }
+
+    let schemas = spec.pointer("/components/schemas").and_then(Value::as_object).cloned().unwrap_or_default();
+
+    if tag.is_none() {
+        for (name, schema) in schemas.iter() {
+            if is_object_schema(schema) {
+                resolver.add_model(&pascal_case(name), schema);
+            }
+        }
+    }
+
+    // models referenced by other models are only known once those are generated
+    while let Some(name) = resolver.referenced.iter().find(|name| !resolver.has_model(&pascal_case(name))).cloned() {
+        match schemas.get(&name) {
+            Some(schema) => resolver.add_model(&pascal_case(&name), schema),
+            None => return Err(format!("Unable to resolve schema {}.", name)),
+        }
+    }
+
+    let base_url = spec.pointer("/servers/0/url").and_then(Value::as_str).map(str::to_string);
+
+    Ok(ApiSpec { models: resolver.models, operations, base_url })
+}
+
+struct Resolver<'a> {
+    spec: &'a Value,
+    models: Vec<DartModel>,
+    /// Names of component schemas referenced so far.
+    referenced: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve<'b>(&self, value: &'b Value) -> &'b Value
+    where
+        'a: 'b,
+    {
+        match value.get("$ref").and_then(Value::as_str) {
+            Some(reference) => self.spec.pointer(reference.trim_start_matches('#')).unwrap_or(value),
+            None => value,
+        }
+    }
+
+    fn has_model(&self, name: &str) -> bool {
+        self.models.iter().any(|model| model.name == name)
+    }
+
+    fn add_model(&mut self, name: &str, schema: &Value) {
+        if self.has_model(name) {
+            return;
+        }
+
+        // reserve the name first so self referencing schemas terminate
+        let index = self.models.len();
+        self.models.push(DartModel { name: name.to_string(), fields: Vec::new() });
+
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+
+        let empty = Map::new();
+        let properties = schema.get("properties").and_then(Value::as_object).unwrap_or(&empty);
+
+        let mut fields: Vec<DartField> = Vec::new();
+        for (key, property) in properties {
+            let mut dart_type = self.schema_type(property, &format!("{}{}", name, pascal_case(key)));
+            if !required.contains(&key.as_str()) {
+                dart_type = nullable(&dart_type);
+            }
+            let field_name = unique_field_name(camel_case(key), &fields);
+            let json_key = if field_name != *key { Some(key.clone()) } else { None };
+            fields.push(DartField { name: field_name, dart_type, json_key });
+        }
+
+        self.models[index].fields = fields;
+    }
+
+    fn schema_type(&mut self, schema: &Value, hint: &str) -> String {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let name = reference.rsplit('/').next().unwrap_or_default().to_string();
+            let target = self.resolve(schema);
+
+            if !is_object_schema(target) {
+                return self.schema_type(target, hint);
+            }
+
+            if !self.referenced.contains(&name) {
+                self.referenced.push(name.clone());
+            }
+            return pascal_case(&name);
+        }
+
+        for combinator in ["allOf", "oneOf", "anyOf"] {
+            if let Some([single]) = schema.get(combinator).and_then(Value::as_array).map(Vec::as_slice) {
+                return self.schema_type(single, hint);
+            }
+        }
+
+        // OpenAPI 3.1 spells nullable types as `type: [string, 'null']`
+        let (schema_type, is_nullable) = match schema.get("type") {
+            Some(Value::Array(types)) => (
+                types.iter().filter_map(Value::as_str).find(|value| *value != "null"),
+                types.iter().any(|value| value.as_str() == Some("null")),
+            ),
+            Some(value) => (value.as_str(), false),
+            None => (None, false),
+        };
+        let is_nullable = is_nullable || schema.get("nullable").and_then(Value::as_bool).unwrap_or(false);
+
+        let dart_type = match schema_type {
+            Some("string") => match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") | Some("date") => "DateTime".to_string(),
+                _ => "String".to_string(),
+            },
+            Some("integer") => "int".to_string(),
+            Some("number") => "double".to_string(),
+            Some("boolean") => "bool".to_string(),
+            Some("array") => {
+                let items = schema.get("items").unwrap_or(&Value::Null);
+                format!("List<{}>", self.schema_type(items, &format!("{}Item", hint)))
+            }
+            _ if schema.get("properties").is_some() => {
+                let name = (1..)
+                    .map(|suffix| if suffix == 1 { hint.to_string() } else { format!("{}{}", hint, suffix) })
+                    .find(|name| !self.has_model(name))
+                    .unwrap_or_default();
+                self.add_model(&name, schema);
+                name
+            }
+            Some("object") => match schema.get("additionalProperties") {
+                Some(Value::Object(values)) => {
+                    let values = Value::Object(values.clone());
+                    format!("Map<String, {}>", self.schema_type(&values, &format!("{}Value", hint)))
+                }
+                _ => "Map<String, dynamic>".to_string(),
+            },
+            _ => return "dynamic".to_string(),
+        };
+
+        if is_nullable { nullable(&dart_type) } else { dart_type }
+    }
+}
+
+fn is_object_schema(schema: &Value) -> bool {
+    schema.get("properties").is_some() || (schema.get("type").and_then(Value::as_str) == Some("object") && schema.get("additionalProperties").is_none())
+}
+
+fn json_schema(container: &Value) -> Option<&Value> {
+    container
+        .get("content")
+        .and_then(Value::as_object)
+        .and_then(|content| content.iter().find(|(media_type, _)| media_type.contains("json")))
+        .and_then(|(_, media)| media.get("schema"))
+}
+
+fn nullable(dart_type: &str) -> String {
+    if dart_type.ends_with('?') || dart_type == "dynamic" {
+        dart_type.to_string()
+    } else {
+        format!("{}?", dart_type)
+    }
+}
+
+/// `/orders/{orderId}/items` becomes `orders by order id items`.
+fn path_words(path: &str) -> String {
+    path.split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| match segment.strip_prefix('{').and_then(|segment| segment.strip_suffix('}')) {
+            Some(param) => format!("by {}", param),
+            None => segment.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+impl Operation {
+    /// Dart signature without a trailing semicolon, e.g. `Future<Order> getOrder(int orderId)`.
+    pub fn signature(&self) -> String {
+        let mut positional: Vec<String> = Vec::new();
+        let mut named: Vec<String> = Vec::new();
+
+        for param in &self.params {
+            let declaration = format!("{} {}", param.dart_type, param.name);
+            if param.dart_type.ends_with('?') {
+                named.push(declaration);
+            } else {
+                positional.push(declaration);
+            }
+        }
+
+        if let Some(body) = &self.body {
+            positional.push(format!("{} body", body));
+        }
+
+        if !named.is_empty() {
+            positional.push(format!("{{{}}}", named.join(", ")));
+        }
+
+        format!("Future<{}> {}({})", self.returns, self.name, positional.join(", "))
+    }
+
+    /// Dart string literal for the request path with path parameters interpolated.
+    fn dart_path(&self) -> String {
+        let mut path = self.path.clone();
+
+        for param in self.params.iter().filter(|param| param.location == ParamLocation::Path) {
+            let placeholder = format!("{{{}}}", param.key);
+            let next = path.find(&placeholder).and_then(|index| path[index + placeholder.len()..].chars().next());
+            let interpolation = match next {
+                Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '.' => format!("${{{}}}", param.name),
+                _ => format!("${}", param.name),
+            };
+            path = path.replace(&placeholder, &interpolation);
+        }
+
+        format!("'{}'", path)
+    }
+
+    /// Body of the remote repository method, performing the request with Dio.
+    pub fn request_body(&self, models: &[DartModel]) -> String {
+        let mut arguments = vec![self.dart_path()];
+
+        let query: Vec<String> = self
+            .params
+            .iter()
+            .filter(|param| param.location == ParamLocation::Query)
+            .map(|param| {
+                if param.dart_type.ends_with('?') {
+                    format!("if ({name} != null) '{}': {name}", param.key, name = param.name)
+                } else {
+                    format!("'{}': {}", param.key, param.name)
+                }
+            })
+            .collect();
+
+        if !query.is_empty() {
+            arguments.push(format!("queryParameters: {{{}}}", query.join(", ")));
+        }
+
+        if let Some(body) = &self.body {
+            arguments.push(format!("data: {}", encode(body, "body", models)));
+        }
+
+        let request = format!("_dio.{}({})", self.method, arguments.join(", "));
+
+        if self.returns == "void" {
+            format!("await {};", request)
+        } else {
+            format!("final response = await {};\nreturn {};", request, decode(&self.returns, "response.data", models))
+        }
+    }
+
+    /// Body of the fake repository method, returning placeholder data.
+    /// Falls back to a `TODO` when the returned model nests too deep to build, see [`Operation::has_fake`].
+    pub fn fake_body(&self, models: &[DartModel]) -> String {
+        if self.returns == "void" {
+            return "{}".to_string();
+        }
+
+        match fake_value(&self.returns, models, 0) {
+            Some(value) => format!("=> {};", value),
+            None => format!(
+                "{{\n                    // TODO: return a fake {}, its required fields nest too deep to build one.\n                    throw UnimplementedError();\n                }}",
+                self.returns
+            ),
+        }
+    }
+
+    /// Whether [`Operation::fake_body`] is able to build placeholder data.
+    pub fn has_fake(&self, models: &[DartModel]) -> bool {
+        self.returns == "void" || fake_value(&self.returns, models, 0).is_some()
+    }
+}
+
+fn is_model(dart_type: &str, models: &[DartModel]) -> bool {
+    models.iter().any(|model| model.name == dart_type)
+}
+
+fn encode(dart_type: &str, value: &str, models: &[DartModel]) -> String {
+    let dart_type = dart_type.trim_end_matches('?');
+
+    match dart_type.strip_prefix("List<").and_then(|inner| inner.strip_suffix('>')) {
+        Some(inner) if is_model(inner, models) => format!("{}.map((e) => e.toJson()).toList()", value),
+        _ if is_model(dart_type, models) => format!("{}.toJson()", value),
+        _ if dart_type == "DateTime" => format!("{}.toIso8601String()", value),
+        _ => value.to_string(),
+    }
+}
+
+fn decode(dart_type: &str, value: &str, models: &[DartModel]) -> String {
+    let dart_type = dart_type.trim_end_matches('?');
+
+    match dart_type.strip_prefix("List<").and_then(|inner| inner.strip_suffix('>')) {
+        Some(inner) if is_model(inner, models) => {
+            format!("({} as List).map((e) => {}.fromJson(e as Map<String, dynamic>)).toList()", value, inner)
+        }
+        Some(inner) => format!("({} as List).cast<{}>()", value, inner),
+        None if is_model(dart_type, models) => format!("{}.fromJson({} as Map<String, dynamic>)", dart_type, value),
+        None if dart_type == "DateTime" => format!("DateTime.parse({} as String)", value),
+        None if dart_type == "dynamic" => value.to_string(),
+        None => format!("{} as {}", value, dart_type),
+    }
+}
+
+/// Placeholder value for a fake, building models from their required fields.
+fn fake_value(dart_type: &str, models: &[DartModel], depth: usize) -> Option<String> {
+    if let Some(value) = dart_default_value(dart_type) {
+        return Some(value);
+    }
+
+    let model = models.iter().find(|model| model.name == dart_type)?;
+
+    if depth > 4 {
+        return None;
+    }
+
+    let arguments = model
+        .fields
+        .iter()
+        .filter(|field| !field.is_nullable())
+        .map(|field| fake_value(&field.dart_type, models, depth + 1).map(|value| format!("{}: {}", field.name, value)))
+        .collect::<Option<Vec<String>>>()?;
+
+    Some(format!("{}({})", model.name, arguments.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operation(path: &str, params: Vec<Param>) -> Operation {
+        Operation { name: "getItem".to_string(), method: "get".to_string(), path: path.to_string(), params, body: None, returns: "void".to_string() }
+    }
+
+    fn path_param(key: &str) -> Param {
+        Param { name: camel_case(key), key: key.to_string(), dart_type: "String".to_string(), location: ParamLocation::Path }
+    }
+
+    #[test]
+    fn operation_parameters_override_path_parameters() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "paths": { "/items": {
+                "parameters": [{ "name": "id", "in": "query", "required": true, "schema": { "type": "integer" } }],
+                "get": { "operationId": "listItems", "parameters": [{ "name": "id", "in": "query", "required": true, "schema": { "type": "string" } }] }
+            } }
+        });
+        let spec = read_spec(&spec, None).unwrap();
+        let params = &spec.operations[0].params;
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].dart_type, "String");
+    }
+
+    #[test]
+    fn nullable_query_parameters_are_named() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "paths": { "/items/{id}": { "get": {
+                "operationId": "getItem",
+                "parameters": [
+                    { "name": "id", "in": "path", "schema": { "type": "integer" } },
+                    { "name": "page_size", "in": "query", "schema": { "type": "integer" } }
+                ]
+            } } }
+        });
+        let spec = read_spec(&spec, None).unwrap();
+        assert_eq!(spec.operations[0].signature(), "Future<void> getItem(int id, {int? pageSize})");
+    }
+
+    #[test]
+    fn references_to_non_object_schemas_are_inlined() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "paths": { "/status": { "get": {
+                "operationId": "getStatus",
+                "responses": { "200": { "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Status" } } } } }
+            } } },
+            "components": { "schemas": { "Status": { "type": "string", "enum": ["up", "down"] } } }
+        });
+        let spec = read_spec(&spec, None).unwrap();
+        assert_eq!(spec.operations[0].returns, "String");
+        assert!(spec.models.is_empty());
+    }
+
+    #[test]
+    fn self_referencing_schemas_terminate() {
+        let spec = json!({
+            "openapi": "3.0.0",
+            "paths": {},
+            "components": { "schemas": { "Node": {
+                "type": "object",
+                "properties": { "parent": { "$ref": "#/components/schemas/Node" } }
+            } } }
+        });
+        let spec = read_spec(&spec, None).unwrap();
+        assert_eq!(spec.models.len(), 1);
+        assert_eq!(spec.models[0].fields[0].dart_type, "Node?");
+    }
+
+    #[test]
+    fn dart_path_braces_interpolations_followed_by_identifier_characters() {
+        assert_eq!(operation("/items/{id}", vec![path_param("id")]).dart_path(), "'/items/$id'");
+        assert_eq!(operation("/files/{name}.json", vec![path_param("name")]).dart_path(), "'/files/${name}.json'");
+        assert_eq!(operation("/users/{user_id}_avatar", vec![path_param("user_id")]).dart_path(), "'/users/${userId}_avatar'");
+    }
+
+    #[test]
+    fn fake_body_leaves_a_todo_for_models_nesting_too_deep() {
+        let node = DartModel { name: "Node".to_string(), fields: vec![DartField::new("parent", "Node")] };
+        let models = vec![node];
+        let mut operation = operation("/nodes", Vec::new());
+        operation.returns = "Node".to_string();
+
+        assert!(!operation.has_fake(&models));
+        assert!(operation.fake_body(&models).contains("// TODO: return a fake Node"));
+    }
+}
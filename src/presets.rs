@@ -0,0 +1,202 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Folder of the project's own presets, one folder per preset holding a preset.json and its templates.
+pub const PRESETS_PATH: &str = ".rudder\\presets";
+
+pub const DEFAULT_PRESET: &str = "standard";
+
+/// Optional parts of the project structure. main.dart, app.dart, the routes, the exceptions, the logger,
+/// the theme, app_colors.dart and .env are always generated since main.dart and app.dart import them.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Part {
+    /// The shared_widgets folder.
+    SharedWidgets,
+    /// app_sizes.dart and app_text_styles.dart.
+    Constants,
+    /// extensions.dart and helper.dart.
+    Utils,
+    /// Tests of the initial features.
+    Tests,
+    /// The light and dark themes wired into app.dart, as `rudder theme` does.
+    Theme,
+    /// The ARB files of `locales`, as `rudder l10n init` does.
+    L10n,
+    /// The entry points of `flavors`, as `rudder flavors add` does.
+    Flavors,
+}
+
+/// What `rudder create --preset` generates.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    #[serde(skip)]
+    pub name: String,
+    pub description: String,
+    /// Built-in preset a custom preset starts from. Its lists are added to the ones of the built-in preset.
+    pub extends: Option<String>,
+    pub parts: Vec<Part>,
+    pub services: Vec<String>,
+    pub locales: Vec<String>,
+    pub flavors: Vec<String>,
+    /// Files rendered from the templates of a custom preset.
+    pub files: Vec<PresetFile>,
+    pub dependencies: Vec<String>,
+    pub dev_dependencies: Vec<String>,
+    /// Folder of a custom preset, `None` for the built-in ones.
+    #[serde(skip)]
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PresetFile {
+    /// Path of the generated file, e.g. `lib/src/utils/analytics_events.dart`.
+    pub path: String,
+    /// Template in the preset folder. `{{app_title}}` and `{{package_name}}` are replaced in it.
+    pub template: String,
+}
+
+struct BuiltinPreset {
+    name: &'static str,
+    description: &'static str,
+    parts: &'static [Part],
+    services: &'static [&'static str],
+    locales: &'static [&'static str],
+    flavors: &'static [&'static str],
+}
+
+const BUILTIN_PRESETS: [BuiltinPreset; 3] = [
+    BuiltinPreset {
+        name: "minimal",
+        description: "Features and routes, with only the files the app needs to start",
+        parts: &[],
+        services: &[],
+        locales: &[],
+        flavors: &[],
+    },
+    BuiltinPreset {
+        name: "standard",
+        description: "Features, routes, constants, utils, shared widgets and tests",
+        parts: &[Part::SharedWidgets, Part::Constants, Part::Utils, Part::Tests],
+        services: &[],
+        locales: &[],
+        flavors: &[],
+    },
+    BuiltinPreset {
+        name: "full",
+        description: "The standard preset with services, the wired theme, localizations and flavors",
+        parts: &[Part::SharedWidgets, Part::Constants, Part::Utils, Part::Tests, Part::Theme, Part::L10n, Part::Flavors],
+        services: &["http_client", "secure_storage", "connectivity"],
+        locales: &["en"],
+        flavors: &["dev", "staging", "prod"],
+    },
+];
+
+impl Preset {
+    pub fn has(&self, part: Part) -> bool {
+        self.parts.contains(&part)
+    }
+
+    /// Path of a template of a custom preset.
+    pub fn template_path(&self, template: &str) -> String {
+        format!("{}\\{}", self.source.as_deref().unwrap_or_default(), template.replace('/', "\\"))
+    }
+}
+
+fn builtin(name: &str) -> Option<Preset> {
+    let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>();
+
+    BUILTIN_PRESETS.iter().find(|preset| preset.name == name).map(|preset| Preset {
+        name: preset.name.to_string(),
+        description: preset.description.to_string(),
+        parts: preset.parts.to_vec(),
+        services: strings(preset.services),
+        locales: strings(preset.locales),
+        flavors: strings(preset.flavors),
+        ..Preset::default()
+    })
+}
+
+/// Reads .rudder/presets/{name}/preset.json, `None` when the project doesn't define the preset.
+fn custom(name: &str) -> Option<Result<Preset, String>> {
+    let source = format!("{}\\{}", PRESETS_PATH, name);
+    let manifest_path = format!("{}\\preset.json", source);
+    let content = std::fs::read_to_string(&manifest_path).ok()?;
+
+    let preset = serde_json::from_str::<Preset>(&content).map_err(|e| format!("Invalid {}: {}", manifest_path, e)).and_then(|preset| {
+        let base = match &preset.extends {
+            Some(extends) => builtin(extends).ok_or_else(|| format!("{} extends the unknown preset '{}'. Expected {}.", manifest_path, extends, builtin_names().join(", ")))?,
+            None => Preset::default(),
+        };
+
+        for file in &preset.files {
+            check_path(&manifest_path, &file.path)?;
+            check_path(&manifest_path, &file.template)?;
+        }
+
+        let mut parts = base.parts;
+        parts.extend(preset.parts.into_iter().filter(|part| !parts.contains(part)).collect::<Vec<Part>>());
+
+        Ok(Preset {
+            name: name.to_string(),
+            description: preset.description,
+            extends: preset.extends,
+            parts,
+            services: [base.services, preset.services].concat(),
+            locales: [base.locales, preset.locales].concat(),
+            flavors: [base.flavors, preset.flavors].concat(),
+            files: preset.files,
+            dependencies: preset.dependencies,
+            dev_dependencies: preset.dev_dependencies,
+            source: Some(source),
+        })
+    });
+
+    Some(preset)
+}
+
+/// Rejects the paths of a custom preset or blueprint leaving its folder or the project: absolute ones and ones with `..`.
+pub fn check_path(manifest_path: &str, path: &str) -> Result<(), String> {
+    let absolute = Path::new(path).is_absolute() || path.starts_with(['/', '\\']) || path.get(1..2) == Some(":");
+
+    if absolute || path.split(['/', '\\']).any(|part| part == "..") {
+        return Err(format!("Invalid {}: '{}' must be a relative path without '..'.", manifest_path, path));
+    }
+    Ok(())
+}
+
+/// Finds a preset, the project's own presets taking precedence over the built-in ones of the same name.
+pub fn find(name: &str) -> Result<Preset, String> {
+    match custom(name) {
+        Some(preset) => preset,
+        None => builtin(name).ok_or_else(|| format!("Unknown preset '{}'. Expected {}. List them with rudder presets list.", name, names().join(", "))),
+    }
+}
+
+fn builtin_names() -> Vec<&'static str> {
+    BUILTIN_PRESETS.iter().map(|preset| preset.name).collect()
+}
+
+/// Names of the project's own presets.
+fn custom_names() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(PRESETS_PATH)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| Path::new(&format!("{}\\{}\\preset.json", PRESETS_PATH, entry.file_name().to_string_lossy())).exists())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Names of every preset, built-in first.
+pub fn names() -> Vec<String> {
+    let mut names: Vec<String> = builtin_names().into_iter().map(str::to_string).collect();
+    names.extend(custom_names().into_iter().filter(|name| !names.contains(name)).collect::<Vec<String>>());
+    names
+}
@@ -0,0 +1,280 @@
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+/// How the commands report what they did.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// Progress lines for people.
+    Human,
+    /// One JSON event per line, ending with a `report` event summarizing the run.
+    Json,
+}
+
+/// How much of the human output is printed. Errors and warnings are always printed.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileStatus {
+    Created,
+    Overwritten,
+    /// An existing file that was edited in place, e.g. an import added to main.dart.
+    Updated,
+    /// A file that was left as it is because it already exists.
+    Skipped,
+}
+
+impl FileStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            FileStatus::Created => "created",
+            FileStatus::Overwritten => "overwritten",
+            FileStatus::Updated => "updated",
+            FileStatus::Skipped => "skipped",
+        }
+    }
+}
+
+/// Codes of the errors and warnings, stable for scripts reading the JSON output.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Code {
+    /// A name, field list or other value given on the command line is invalid.
+    InvalidArgument,
+    /// A file the command reads (spec, tokens, JSON sample, config) is malformed.
+    InvalidInput,
+    /// A file or feature the command needs doesn't exist.
+    NotFound,
+    /// What the command would add already exists.
+    AlreadyExists,
+    /// The code couldn't be wired automatically and needs a manual edit.
+    ManualStep,
+    /// A removed constant is still referenced.
+    StaleReference,
+    Io,
+    DependencyFailed,
+    /// An upgraded file has edits conflicting with the new template.
+    MergeConflict,
+}
+
+impl Code {
+    fn as_str(self) -> &'static str {
+        match self {
+            Code::InvalidArgument => "invalid_argument",
+            Code::InvalidInput => "invalid_input",
+            Code::NotFound => "not_found",
+            Code::AlreadyExists => "already_exists",
+            Code::ManualStep => "manual_step",
+            Code::StaleReference => "stale_reference",
+            Code::Io => "io_error",
+            Code::DependencyFailed => "dependency_failed",
+            Code::MergeConflict => "merge_conflict",
+        }
+    }
+}
+
+struct Reporter {
+    format: Format,
+    verbosity: Verbosity,
+    files: Vec<Value>,
+    backups: Vec<Value>,
+    folders: Vec<String>,
+    dependencies: Vec<Value>,
+    warnings: Vec<Value>,
+    errors: Vec<Value>,
+}
+
+static REPORTER: Mutex<Reporter> = Mutex::new(Reporter {
+    format: Format::Human,
+    verbosity: Verbosity::Normal,
+    files: Vec::new(),
+    backups: Vec::new(),
+    folders: Vec::new(),
+    dependencies: Vec::new(),
+    warnings: Vec::new(),
+    errors: Vec::new(),
+});
+
+fn reporter() -> std::sync::MutexGuard<'static, Reporter> {
+    REPORTER.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Sets the output format and verbosity, before any command runs.
+pub fn configure(format: Format, verbosity: Verbosity) {
+    let mut reporter = reporter();
+    reporter.format = format;
+    reporter.verbosity = verbosity;
+}
+
+pub fn is_json() -> bool {
+    reporter().format == Format::Json
+}
+
+fn emit(reporter: &Reporter, event: Value) {
+    if reporter.format == Format::Json {
+        println!("{}", event);
+    }
+}
+
+fn print(reporter: &Reporter, verbosity: Verbosity, message: &str) {
+    if reporter.format == Format::Human && reporter.verbosity >= verbosity {
+        println!("{}", message);
+    }
+}
+
+/// A progress message, hidden by `--quiet`.
+pub fn info(message: &str) {
+    let reporter = reporter();
+    print(&reporter, Verbosity::Normal, message);
+    emit(&reporter, json!({ "event": "info", "message": message }));
+}
+
+/// A message only shown with `--verbose`.
+pub fn detail(message: &str) {
+    let reporter = reporter();
+    print(&reporter, Verbosity::Verbose, message);
+    if reporter.verbosity == Verbosity::Verbose {
+        emit(&reporter, json!({ "event": "detail", "message": message }));
+    }
+}
+
+/// Something the command couldn't do on its own, while the rest of it went through.
+pub fn warning(code: Code, message: &str) {
+    let mut reporter = reporter();
+    let event = json!({ "event": "warning", "code": code.as_str(), "message": message });
+    print(&reporter, Verbosity::Quiet, &format!("**{}**", message));
+    emit(&reporter, event.clone());
+    reporter.warnings.push(event);
+}
+
+/// A failure that stopped the command. Makes rudder exit with a non-zero code.
+pub fn error(code: Code, message: &str) {
+    let mut reporter = reporter();
+    let event = json!({ "event": "error", "code": code.as_str(), "message": message });
+    print(&reporter, Verbosity::Quiet, &format!("**{}**", message));
+    emit(&reporter, event.clone());
+    reporter.errors.push(event);
+}
+
+pub fn file(path: &str, status: FileStatus) {
+    let mut reporter = reporter();
+    match status {
+        FileStatus::Created | FileStatus::Overwritten => print(&reporter, Verbosity::Normal, &format!("Adding {}", path)),
+        FileStatus::Updated => print(&reporter, Verbosity::Verbose, &format!("Updating {}", path)),
+        FileStatus::Skipped => print(&reporter, Verbosity::Verbose, &format!("Skipping {}, it already exists", path)),
+    }
+
+    let event = json!({ "event": "file", "path": path, "status": status.as_str() });
+    emit(&reporter, event.clone());
+
+    // a file written several times is reported once, created wins over updated
+    match reporter.files.iter_mut().find(|file| file["path"] == path) {
+        Some(file) if status != FileStatus::Updated && status != FileStatus::Skipped => *file = event,
+        Some(_) => (),
+        None => reporter.files.push(event),
+    }
+}
+
+/// A file copied before it was overwritten.
+pub fn backup(path: &str, backup: &str) {
+    let mut reporter = reporter();
+    print(&reporter, Verbosity::Normal, &format!("Backing up {} to {}", path, backup));
+    let event = json!({ "event": "backup", "path": path, "backup": backup });
+    emit(&reporter, event.clone());
+    reporter.backups.push(event);
+}
+
+pub fn folder(path: &str) {
+    let mut reporter = reporter();
+    print(&reporter, Verbosity::Verbose, &format!("Creating {}", path));
+    emit(&reporter, json!({ "event": "folder", "path": path }));
+    reporter.folders.push(path.to_string());
+}
+
+/// A unified diff of a file, printed whatever the verbosity since it's what the command was run for.
+pub fn diff(path: &str, patch: &str) {
+    let reporter = reporter();
+    if reporter.format == Format::Human {
+        print!("{}", patch);
+    }
+    emit(&reporter, json!({ "event": "diff", "path": path, "patch": patch }));
+}
+
+/// Result of a `flutter pub add`.
+pub fn dependencies(packages: &[&str], dev: bool, result: Result<(), String>) {
+    let mut reporter = reporter();
+    let label = if dev { "dev dependencies" } else { "dependencies" };
+
+    let event = match &result {
+        Ok(()) => {
+            print(&reporter, Verbosity::Normal, &format!("{}{} installed successfully!", label[..1].to_uppercase(), &label[1..]));
+            json!({ "event": "dependencies", "packages": packages, "dev": dev, "success": true })
+        }
+        Err(e) => {
+            print(&reporter, Verbosity::Quiet, &format!("**Unable to install {}: {}**", packages.join(", "), e));
+            json!({ "event": "dependencies", "packages": packages, "dev": dev, "success": false, "code": Code::DependencyFailed.as_str(), "message": e })
+        }
+    };
+
+    emit(&reporter, event.clone());
+    if result.is_err() {
+        reporter.errors.push(json!({ "event": "error", "code": Code::DependencyFailed.as_str(), "message": format!("Unable to install {}.", packages.join(", ")) }));
+    }
+    reporter.dependencies.push(event);
+}
+
+/// Ends the run: prints the final report in JSON mode and returns the exit code.
+pub fn finish() -> i32 {
+    let reporter = reporter();
+    let success = reporter.errors.is_empty();
+
+    let with_status = |status: FileStatus| -> Vec<&Value> {
+        reporter.files.iter().filter(|file| file["status"] == status.as_str()).map(|file| &file["path"]).collect()
+    };
+
+    emit(
+        &reporter,
+        json!({
+            "event": "report",
+            "success": success,
+            "files": {
+                "created": with_status(FileStatus::Created),
+                "overwritten": with_status(FileStatus::Overwritten),
+                "updated": with_status(FileStatus::Updated),
+                "skipped": with_status(FileStatus::Skipped),
+            },
+            "backups": reporter.backups,
+            "folders": reporter.folders,
+            "dependencies": reporter.dependencies,
+            "warnings": reporter.warnings,
+            "errors": reporter.errors,
+        }),
+    );
+
+    // existing files are easy to miss among the progress lines
+    let skipped = with_status(FileStatus::Skipped);
+    if !skipped.is_empty() {
+        print(&reporter, Verbosity::Normal, "\nSkipped, already existing:");
+        for path in &skipped {
+            print(&reporter, Verbosity::Normal, &format!("  {}", path.as_str().unwrap_or_default()));
+        }
+    }
+
+    let overwritten = with_status(FileStatus::Overwritten);
+    if !overwritten.is_empty() {
+        print(&reporter, Verbosity::Normal, "\nReplaced:");
+        for path in &overwritten {
+            let backup = reporter.backups.iter().find(|backup| backup["path"] == **path).and_then(|backup| backup["backup"].as_str());
+            match backup {
+                Some(backup) => print(&reporter, Verbosity::Normal, &format!("  {} (backup: {})", path.as_str().unwrap_or_default(), backup)),
+                None => print(&reporter, Verbosity::Normal, &format!("  {}", path.as_str().unwrap_or_default())),
+            }
+        }
+    }
+
+    if success { 0 } else { 1 }
+}
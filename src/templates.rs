@@ -0,0 +1,135 @@
+use serde_json::{Map, Value};
+
+use crate::dart::*;
+use crate::model::parse_states;
+use crate::theme;
+
+/// Values a template is rendered with, as recorded in the manifest.
+pub type Variables = Map<String, Value>;
+
+pub struct Template {
+    pub id: &'static str,
+    /// Bumped whenever the rendered content of the template changes.
+    pub version: u32,
+    /// `None` for files derived from other project files (.env, an OpenAPI spec, a tokens file),
+    /// which are regenerated by the command that created them instead.
+    pub render: Option<fn(&Variables) -> String>,
+}
+
+const fn template(id: &'static str, render: fn(&Variables) -> String) -> Template {
+    Template { id, version: 1, render: Some(render) }
+}
+
+const fn derived(id: &'static str) -> Template {
+    Template { id, version: 1, render: None }
+}
+
+/// Every template rudder generates files from.
+pub const TEMPLATES: [Template; 58] = [
+    template("empty", |_| String::new()),
+    template("main", |_| main_dart()),
+    // 2: imports routes/routes.dart
+    Template { version: 2, ..template("app", |vars| app_dart(text(vars, "app_title"))) },
+    template("go_router_app", |vars| go_router_app_dart(text(vars, "app_title"))),
+    template("routes", |_| routes_dart()),
+    template("go_router_routes", |vars| go_router_dart(text(vars, "initial_location"))),
+    template("extensions", |_| extensions_dart()),
+    template("helper", |_| helper_dart()),
+    template("app_sizes", |_| app_sizes()),
+    template("app_colors", |vars| app_colors(&theme::palette(theme::parse_hex_color(text(vars, "seed")).or_else(|_| theme::parse_hex_color(theme::DEFAULT_SEED)).expect("Invalid default seed")))),
+    template("app_text_styles", |_| app_text_styles()),
+    // 2: AppException is a sealed class
+    Template { version: 2, ..template("app_exceptions", |_| app_exceptions()) },
+    template("async_errors", |_| async_errors()),
+    template("theme", |vars| theme_dart(text(vars, "mode"))),
+    template("logger_service", |_| logger_service()),
+    // 2: the analytics service initializes Firebase
+    Template { version: 2, ..template("service", |vars| crate::service_content(text(vars, "name"))) },
+    template("fake_overrides", |_| fake_overrides_dart()),
+    template("application_service", |vars| application_service(text(vars, "feature"))),
+    template("local_repository", |vars| local_repository(text(vars, "feature"))),
+    template("remote_repository", |vars| remote_repository(text(vars, "feature"))),
+    template("fake_local_repository", |vars| fake_local_repository(text(vars, "feature"))),
+    template("fake_remote_repository", |vars| fake_remote_repository(text(vars, "feature"))),
+    template("feature_exceptions", |vars| feature_exceptions(text(vars, "feature"), text(vars, "exceptions_import"))),
+    // 2: the union is a sealed class
+    Template { version: 2, ..template("union_states", |vars| union_states(text(vars, "feature"), &parse_states(text(vars, "states")).unwrap_or_default())) },
+    template("screen", |vars| stateless_widget(text(vars, "feature"), flag(vars, "auto_route"))),
+    template("localized_screen", |vars| localized_stateless_widget(text(vars, "feature"), text(vars, "l10n_import"), flag(vars, "auto_route"))),
+    template("controller", |vars| controller(text(vars, "feature"))),
+    template("state_controller", |vars| state_controller(text(vars, "feature"), text(vars, "initial_state"))),
+    template("test_overrides", |vars| test_overrides(text(vars, "package_name"), text(vars, "feature_import"), text(vars, "feature"), &list(vars, "kinds"))),
+    template("service_test", |vars| service_test(text(vars, "package_name"), text(vars, "feature_import"), text(vars, "feature"))),
+    template("controller_test", |vars| {
+        controller_test(text(vars, "package_name"), text(vars, "feature_import"), text(vars, "feature"), vars.get("initial_state").and_then(Value::as_str))
+    }),
+    template("screen_test", |vars| screen_test(text(vars, "package_name"), text(vars, "feature_import"), text(vars, "feature"), flag(vars, "localized"))),
+    template("flavor", |vars| flavor_dart(&list(vars, "flavors"))),
+    template("flavor_main", |vars| flavor_main_dart(text(vars, "flavor"))),
+    template("l10n_yaml", |vars| l10n_yaml(text(vars, "template_locale"))),
+    template("l10n", |_| l10n_dart()),
+    template("widget", |vars| widget(text(vars, "class_name"), text(vars, "kind"))),
+    template("widget_test", |vars| widget_test(text(vars, "package_name"), text(vars, "widget_import"), text(vars, "class_name"), text(vars, "kind"))),
+    template("shape_service", |vars| shape_service(text(vars, "feature"), text(vars, "shape"))),
+    template("shape_repository", |vars| shape_repository(text(vars, "feature"), text(vars, "shape"))),
+    template("shape_fake_repository", |vars| shape_fake_repository(text(vars, "feature"), text(vars, "shape"))),
+    template("list_controller", |vars| list_controller(text(vars, "feature"))),
+    template("readonly_controller", |vars| readonly_controller(text(vars, "feature"))),
+    template("form_controller", |vars| form_controller(text(vars, "feature"))),
+    template("settings_controller", |vars| settings_controller(text(vars, "feature"))),
+    template("list_screen", |vars| list_screen(text(vars, "feature"), flag(vars, "auto_route"), text(vars, "l10n_import"), text(vars, "location"))),
+    template("detail_screen", |vars| detail_screen(text(vars, "feature"), flag(vars, "auto_route"), text(vars, "l10n_import"))),
+    template("readonly_screen", |vars| readonly_screen(text(vars, "feature"), flag(vars, "auto_route"), text(vars, "l10n_import"))),
+    template("form_screen", |vars| form_screen(text(vars, "feature"), flag(vars, "auto_route"), text(vars, "l10n_import"))),
+    template("settings_screen", |vars| settings_screen(text(vars, "feature"), flag(vars, "auto_route"), text(vars, "l10n_import"))),
+    derived("env"),
+    derived("flavor_env"),
+    derived("bootstrap"),
+    derived("api_remote_repository"),
+    derived("api_fake_remote_repository"),
+    derived("token_colors"),
+    derived("token_sizes"),
+    derived("token_text_styles"),
+];
+
+/// Template read from a file of the project, e.g. of a custom preset, whose path is the `source` variable.
+/// Each `{{name}}` in it is replaced by the variable of that name.
+pub const FILE_TEMPLATE: &str = "file";
+
+pub fn find(id: &str) -> Option<&'static Template> {
+    TEMPLATES.iter().find(|template| template.id == id)
+}
+
+/// Renders a template of the registry, `None` when it's unknown or derived from other files.
+pub fn render(id: &str, variables: &Variables) -> Option<String> {
+    if id == FILE_TEMPLATE {
+        return render_file(variables);
+    }
+
+    find(id).and_then(|template| template.render).map(|render| render(variables))
+}
+
+/// Renders a file template, `None` when its file doesn't exist anymore.
+fn render_file(variables: &Variables) -> Option<String> {
+    let content = std::fs::read_to_string(text(variables, "source")).ok()?;
+
+    Some(variables.iter().filter(|(key, _)| *key != "source").fold(content, |content, (key, value)| {
+        content.replace(&format!("{{{{{}}}}}", key), &value.as_str().map(str::to_string).unwrap_or_else(|| value.to_string()))
+    }))
+}
+
+fn text<'a>(variables: &'a Variables, key: &str) -> &'a str {
+    variables.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn flag(variables: &Variables, key: &str) -> bool {
+    variables.get(key).and_then(Value::as_bool).unwrap_or_default()
+}
+
+fn list(variables: &Variables, key: &str) -> Vec<String> {
+    variables
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|values| values.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
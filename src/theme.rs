@@ -0,0 +1,101 @@
+/// Seed color used when neither `--seed` nor the project config sets one.
+pub const DEFAULT_SEED: &str = "#6750A4";
+
+/// An opaque color, rendered as a Dart `Color(0xFFRRGGBB)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub fn to_dart(self) -> String {
+        format!("Color(0xFF{:02X}{:02X}{:02X})", self.0, self.1, self.2)
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+    }
+}
+
+/// A named color of the generated `AppColors` class.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteColor {
+    pub name: String,
+    pub description: String,
+    pub color: Rgb,
+}
+
+/// Parses `#6750A4`, `6750A4` or `0xFF6750A4`.
+pub fn parse_hex_color(value: &str) -> Result<Rgb, String> {
+    let value = value.trim();
+    let hex = value.strip_prefix('#').or_else(|| value.strip_prefix("0x").or_else(|| value.strip_prefix("0X"))).unwrap_or(value);
+
+    // only ASCII digits can be sliced by byte
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) || (hex.len() != 6 && hex.len() != 8) {
+        return Err(format!("Invalid color '{}'. Expected a hex color such as #6750A4.", value));
+    }
+
+    let hex = if hex.len() == 8 { &hex[2..] } else { hex };
+
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).expect("Invalid hex digit");
+    Ok(Rgb(channel(0), channel(2), channel(4)))
+}
+
+/// The `AppColors` tokens derived from a seed color: the seed itself, plus text, background and
+/// error colors for the light and dark themes, tinted with the seed's hue.
+pub fn palette(seed: Rgb) -> Vec<PaletteColor> {
+    let (hue, saturation, lightness) = to_hsl(seed);
+
+    let color = |name: &str, description: &str, color: Rgb| PaletteColor { name: name.to_string(), description: description.to_string(), color };
+
+    vec![
+        color("seed", "Seed of the light and dark color schemes", seed),
+        color("kPrimary", "Primary color of the light theme", seed),
+        color("kPrimaryDark", "Primary color of the dark theme", from_hsl(hue, saturation, lightness.max(0.75))),
+        color("kText", "Text color of the light theme", from_hsl(hue, 0.15, 0.12)),
+        color("kTextDark", "Text color of the dark theme", from_hsl(hue, 0.10, 0.92)),
+        color("kBackground", "Scaffold background of the light theme", from_hsl(hue, 0.20, 0.98)),
+        color("kBackgroundDark", "Scaffold background of the dark theme", from_hsl(hue, 0.10, 0.08)),
+        color("kError", "Error color of the light theme", Rgb(0xB3, 0x26, 0x1E)),
+        color("kErrorDark", "Error color of the dark theme", Rgb(0xF2, 0xB8, 0xB5)),
+    ]
+}
+
+fn to_hsl(Rgb(r, g, b): Rgb) -> (f64, f64, f64) {
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * (((g - b) / delta).rem_euclid(6.0))
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+
+    (hue, saturation, lightness)
+}
+
+fn from_hsl(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+
+    let (r, g, b) = match hue {
+        h if h < 60.0 => (chroma, x, 0.0),
+        h if h < 120.0 => (x, chroma, 0.0),
+        h if h < 180.0 => (0.0, chroma, x),
+        h if h < 240.0 => (0.0, x, chroma),
+        h if h < 300.0 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    let channel = |value: f64| ((value + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
@@ -0,0 +1,277 @@
+use serde_json::{Map, Value};
+
+use crate::model::camel_case;
+
+/// A color token, e.g. `primary` -> `Color(0xFF6750A4)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorToken {
+    pub name: String,
+    pub color: String,
+}
+
+/// A typography token, rendered as a `TextStyle` of `AppTextStyles`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyleToken {
+    pub name: String,
+    pub font_family: Option<String>,
+    pub font_size: Option<f64>,
+    pub font_weight: Option<u32>,
+    pub letter_spacing: Option<f64>,
+    pub height: Option<f64>,
+}
+
+/// Tokens of a design tokens file, grouped by the constants file they end up in.
+#[derive(Debug, Default, PartialEq)]
+pub struct DesignTokens {
+    pub colors: Vec<ColorToken>,
+    /// Distinct spacing/sizing values, sorted, each becoming a `Sizes.pN` constant.
+    pub sizes: Vec<f64>,
+    pub text_styles: Vec<TextStyleToken>,
+}
+
+/// Reads a design tokens export, either in the Tokens Studio/W3C format where each token is an
+/// object with a `value` (or `$value`) and a `type`, or as plain values grouped under
+/// `colors`, `sizes`/`spacing` and `typography`/`textStyles`.
+pub fn parse_tokens(content: &str) -> Result<DesignTokens, String> {
+    let root: Value = serde_json::from_str(content).map_err(|e| format!("Invalid tokens file: {}", e))?;
+    let root = root.as_object().ok_or("Invalid tokens file: expected a JSON object.")?;
+
+    let mut leaves = Vec::new();
+    collect_tokens(root, &mut Vec::new(), None, &mut leaves);
+
+    let mut tokens = DesignTokens::default();
+
+    for (path, token_type, value) in &leaves {
+        let value = resolve_alias(root, value, 0)?;
+        let name = token_name(path);
+
+        match token_type.as_str() {
+            "color" => {
+                let color = value.as_str().ok_or(format!("Color token '{}' is not a string.", path.join(".")))?;
+                tokens.colors.push(ColorToken { name, color: dart_color(color)? });
+            }
+            // unitless numbers (opacities, z-indexes...) aren't sizes
+            "dimension" | "spacing" | "sizing" | "size" => {
+                if let Some(size) = number(&value).filter(|size| size.is_finite()) {
+                    if !tokens.sizes.contains(&size) {
+                        tokens.sizes.push(size);
+                    }
+                }
+            }
+            "typography" => {
+                let style = value.as_object().ok_or(format!("Typography token '{}' is not an object.", path.join(".")))?;
+                tokens.text_styles.push(text_style(root, name, style)?);
+            }
+            _ => (),
+        }
+    }
+
+    tokens.sizes.sort_by(f64::total_cmp);
+
+    Ok(tokens)
+}
+
+/// Walks the groups of the tokens file, collecting `(path, type, value)` for every token.
+fn collect_tokens(group: &Map<String, Value>, path: &mut Vec<String>, inherited_type: Option<String>, leaves: &mut Vec<(Vec<String>, String, Value)>) {
+    // W3C groups can set the type of all their tokens
+    let group_type = group.get("$type").and_then(Value::as_str).map(str::to_string).or(inherited_type);
+
+    for (key, value) in group {
+        if key.starts_with('$') {
+            continue;
+        }
+
+        path.push(key.clone());
+
+        match value {
+            Value::Object(object) if object.contains_key("value") || object.contains_key("$value") => {
+                let token_value = object.get("$value").or_else(|| object.get("value")).cloned().unwrap_or_default();
+                let token_type = object
+                    .get("$type")
+                    .or_else(|| object.get("type"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .or_else(|| group_type.clone())
+                    .or_else(|| type_from_group(&path[0]));
+
+                if let Some(token_type) = token_type {
+                    leaves.push((path.clone(), normalize_type(&token_type), token_value));
+                }
+            }
+            Value::Object(object) => collect_tokens(object, path, group_type.clone(), leaves),
+            _ => {
+                if let Some(token_type) = group_type.clone().or_else(|| type_from_group(&path[0])) {
+                    leaves.push((path.clone(), normalize_type(&token_type), value.clone()));
+                }
+            }
+        }
+
+        path.pop();
+    }
+}
+
+/// Type of the plain values of a top level group, e.g. `colors` -> `color`.
+fn type_from_group(group: &str) -> Option<String> {
+    let token_type = match group.to_lowercase().as_str() {
+        "color" | "colors" => "color",
+        "size" | "sizes" | "sizing" | "spacing" | "space" | "dimension" | "dimensions" => "spacing",
+        "typography" | "textstyles" | "text_styles" | "text-styles" => "typography",
+        _ => return None,
+    };
+    Some(token_type.to_string())
+}
+
+fn normalize_type(token_type: &str) -> String {
+    match token_type.to_lowercase().as_str() {
+        "borderradius" | "borderwidth" => "dimension".to_string(),
+        token_type => token_type.to_string(),
+    }
+}
+
+/// Dart name of a token, without its top level group: `color.brand.500` -> `brand500`.
+fn token_name(path: &[String]) -> String {
+    let path = if path.len() > 1 { &path[1..] } else { path };
+    camel_case(&path.join("_"))
+}
+
+/// Follows `{color.primary}` references to the value they point to.
+fn resolve_alias(root: &Map<String, Value>, value: &Value, depth: usize) -> Result<Value, String> {
+    let reference = match value.as_str().map(str::trim) {
+        Some(reference) if reference.starts_with('{') && reference.ends_with('}') => &reference[1..reference.len() - 1],
+        _ => return Ok(value.clone()),
+    };
+
+    if depth > 10 {
+        return Err(format!("Token reference '{{{}}}' is circular.", reference));
+    }
+
+    let mut target = root.get(reference.split('.').next().unwrap_or_default());
+    for key in reference.split('.').skip(1) {
+        target = target.and_then(|target| target.get(key));
+    }
+
+    let target = target.ok_or(format!("Token reference '{{{}}}' doesn't exist.", reference))?;
+    let target = match target {
+        Value::Object(object) => object.get("$value").or_else(|| object.get("value")).unwrap_or(target),
+        _ => target,
+    };
+
+    resolve_alias(root, target, depth + 1)
+}
+
+fn text_style(root: &Map<String, Value>, name: String, style: &Map<String, Value>) -> Result<TextStyleToken, String> {
+    let property = |key: &str| -> Result<Option<Value>, String> {
+        match style.get(key) {
+            Some(value) => resolve_alias(root, value, 0).map(Some),
+            None => Ok(None),
+        }
+    };
+
+    let font_size = property("fontSize")?.as_ref().and_then(number);
+
+    // line heights are either a multiplier or a pixel value
+    let height = match property("lineHeight")? {
+        Some(Value::String(line_height)) if line_height.ends_with('%') => line_height.trim_end_matches('%').trim().parse::<f64>().ok().map(|percent| percent / 100.0),
+        Some(line_height) => number(&line_height).map(|line_height| match font_size {
+            Some(font_size) if line_height > 4.0 => line_height / font_size,
+            _ => line_height,
+        }),
+        None => None,
+    };
+
+    // letter spacing can be given in percent of the font size
+    let letter_spacing = match property("letterSpacing")? {
+        Some(Value::String(spacing)) if spacing.ends_with('%') => spacing
+            .trim_end_matches('%')
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|percent| percent / 100.0 * font_size.unwrap_or(14.0)),
+        Some(spacing) => number(&spacing),
+        None => None,
+    };
+
+    Ok(TextStyleToken {
+        name,
+        font_family: property("fontFamily")?.and_then(|family| family.as_str().map(str::to_string)),
+        font_size,
+        font_weight: property("fontWeight")?.and_then(|weight| font_weight(&weight)),
+        letter_spacing,
+        height: height.map(|height| (height * 100.0).round() / 100.0),
+    })
+}
+
+/// Reads `16`, `"16"` or `"16px"`.
+fn number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(value) => value.trim().trim_end_matches("px").trim_end_matches("dp").trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Reads `400`, `"400"` or named weights such as `"Semi Bold"`.
+fn font_weight(value: &Value) -> Option<u32> {
+    if let Some(weight) = number(value) {
+        let weight = ((weight / 100.0).round() * 100.0).clamp(100.0, 900.0);
+        return Some(weight as u32);
+    }
+
+    let weight = match value.as_str()?.to_lowercase().replace([' ', '-', '_'], "").as_str() {
+        "thin" | "hairline" => 100,
+        "extralight" | "ultralight" => 200,
+        "light" => 300,
+        "regular" | "normal" | "book" => 400,
+        "medium" => 500,
+        "semibold" | "demibold" => 600,
+        "bold" => 700,
+        "extrabold" | "ultrabold" => 800,
+        "black" | "heavy" => 900,
+        _ => return None,
+    };
+    Some(weight)
+}
+
+/// Dart color of `#RGB`, `#RRGGBB`, `#RRGGBBAA` or `rgb(a)(r, g, b[, a])`.
+pub fn dart_color(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let invalid = || format!("Invalid color '{}'.", value);
+
+    if let Some(arguments) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")).and_then(|value| value.strip_suffix(')')) {
+        let parts: Vec<f64> = arguments.split(',').map(|part| part.trim().parse::<f64>()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+        let (r, g, b, a) = match parts[..] {
+            [r, g, b] => (r, g, b, 1.0),
+            [r, g, b, a] => (r, g, b, a),
+            _ => return Err(invalid()),
+        };
+        let channel = |value: f64| value.round().clamp(0.0, 255.0) as u8;
+        return Ok(format!("Color(0x{:02X}{:02X}{:02X}{:02X})", channel(a * 255.0), channel(r), channel(g), channel(b)));
+    }
+
+    let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+
+    let argb = match hex.len() {
+        3 => format!("FF{}", hex.chars().flat_map(|c| [c, c]).collect::<String>()),
+        6 => format!("FF{}", hex),
+        // CSS puts the alpha last, Dart first
+        8 => format!("{}{}", &hex[6..], &hex[..6]),
+        _ => return Err(invalid()),
+    };
+
+    Ok(format!("Color(0x{})", argb.to_uppercase()))
+}
+
+/// Name of the `Sizes` constant of a value, e.g. `12.0` -> `p12`, `12.5` -> `p12_5`, `-4.0` -> `n4`.
+pub fn size_name(size: f64) -> String {
+    let prefix = if size < 0.0 { "n" } else { "p" };
+    format!("{}{}", prefix, dart_number(size.abs()).trim_end_matches(".0").replace('.', "_"))
+}
+
+/// A Dart double literal, e.g. `4.0`.
+pub fn dart_number(value: f64) -> String {
+    let value = (value * 1000.0).round() / 1000.0;
+    if value.fract() == 0.0 { format!("{:.1}", value) } else { value.to_string() }
+}
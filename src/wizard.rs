@@ -0,0 +1,102 @@
+use dialoguer::{Confirm, Input, MultiSelect, Select};
+
+use crate::config::{ProjectConfig, ROUTERS, STATE_MANAGEMENTS};
+use crate::split_list;
+
+const STATE_MANAGEMENT_LABELS: [&str; 2] = ["Riverpod controllers", "Riverpod controllers with freezed union states"];
+const ROUTER_LABELS: [&str; 2] = ["auto_route", "go_router"];
+
+/// Asks for the answers of `rudder create`, suggesting the ones already given as flags.
+pub fn ask(config: ProjectConfig) -> Result<ProjectConfig, String> {
+    let presets = crate::presets::names();
+    let preset = Select::new()
+        .with_prompt("Preset")
+        .items(&presets)
+        .default(presets.iter().position(|name| Some(name.as_str()) == config.preset.as_deref().or(Some(crate::presets::DEFAULT_PRESET))).unwrap_or(0))
+        .interact()
+        .map_err(|e| e.to_string())?;
+
+    let app_title: String = Input::new()
+        .with_prompt("App title")
+        .default(config.app_title.clone().unwrap_or_default())
+        .interact_text()
+        .map_err(|e| e.to_string())?;
+
+    let package_name: String = Input::new()
+        .with_prompt("Package name")
+        .default(config.package_name.clone().unwrap_or_else(|| "app".to_string()))
+        .validate_with(|name: &String| -> Result<(), String> {
+            ProjectConfig { package_name: Some(name.clone()), ..ProjectConfig::default() }.validate()
+        })
+        .interact_text()
+        .map_err(|e| e.to_string())?;
+
+    let state_management = Select::new()
+        .with_prompt("State management")
+        .items(&STATE_MANAGEMENT_LABELS)
+        .default(STATE_MANAGEMENTS.iter().position(|value| *value == config.state_management).unwrap_or(0))
+        .interact()
+        .map_err(|e| e.to_string())?;
+
+    let router = Select::new()
+        .with_prompt("Router")
+        .items(&ROUTER_LABELS)
+        .default(ROUTERS.iter().position(|value| *value == config.router).unwrap_or(0))
+        .interact()
+        .map_err(|e| e.to_string())?;
+
+    let kinds = crate::service_kinds();
+    let selected: Vec<bool> = kinds.iter().map(|kind| config.services.iter().any(|service| service == kind)).collect();
+    let services = MultiSelect::new()
+        .with_prompt("Services (space to select, enter to confirm)")
+        .items(&kinds)
+        .defaults(&selected)
+        .interact()
+        .map_err(|e| e.to_string())?;
+
+    let features: String = Input::new()
+        .with_prompt("Initial features (comma separated)")
+        .default(config.features.join(","))
+        .interact_text()
+        .map_err(|e| e.to_string())?;
+
+    let install_dependencies = Confirm::new()
+        .with_prompt("Install the dependencies?")
+        .default(config.install_dependencies)
+        .interact()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ProjectConfig {
+        preset: Some(presets[preset].clone()),
+        app_title: Some(app_title),
+        package_name: Some(package_name),
+        state_management: STATE_MANAGEMENTS[state_management].to_string(),
+        router: ROUTERS[router].to_string(),
+        services: services.into_iter().map(|index| kinds[index].to_string()).collect(),
+        features: split_list(&features),
+        install_dependencies,
+        ..config
+    })
+}
+
+pub fn confirm(prompt: &str) -> bool {
+    Confirm::new().with_prompt(prompt).default(true).interact().unwrap_or(false)
+}
+
+/// Answers of `rudder create`, shown before anything is written.
+pub fn summary(config: &ProjectConfig) -> String {
+    let list = |values: &[String]| if values.is_empty() { "none".to_string() } else { values.join(", ") };
+    let state_management = STATE_MANAGEMENTS.iter().position(|value| *value == config.state_management).map_or(config.state_management.as_str(), |index| STATE_MANAGEMENT_LABELS[index]);
+
+    format!(
+        "\nPreset:           {}\nApp title:        {}\nPackage name:     {}\nState management: {}\nRouter:           {}\nServices:         {}\nFeatures:         {}\nDependencies:     {}\n",
+        config.preset.as_deref().unwrap_or(crate::presets::DEFAULT_PRESET),
+        config.app_title.as_deref().unwrap_or_default(),
+        config.package_name.as_deref().unwrap_or_default(),
+        state_management,
+        config.router,
+        list(&config.services),
+        list(&config.features),
+        if config.install_dependencies { "install" } else { "skip" },
+    )
+}